@@ -3,4 +3,23 @@ use day1_calorie_counting::*;
 fn main() {
     println!("Part 1: {}", part1(&data()));
     println!("Part 2: {}", part2(&data()));
+
+    let plan = rebalance::plan(&inventories(&data()));
+    println!(
+        "Rebalanced: {} moves, max load {}",
+        plan.moves.len(),
+        plan.max_load
+    );
+    // Elves are numbered from 1 in the order of the input
+    for m in &plan.moves {
+        println!(
+            "Move {} calories from elf {} to elf {}",
+            m.calories,
+            m.from + 1,
+            m.to + 1
+        );
+    }
+    for (i, load) in plan.loads.iter().enumerate() {
+        println!("Elf {}: {load} calories", i + 1);
+    }
 }
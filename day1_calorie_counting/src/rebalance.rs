@@ -0,0 +1,309 @@
+use std::cmp::Reverse;
+
+// Redistribute whole food items between elves so the heaviest load is as
+// small as possible (multiway number partitioning).
+
+// Inputs with at most this many food items are solved exactly.
+pub const EXACT_LIMIT: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub calories: u32,
+    pub from: usize,
+    pub to: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub moves: Vec<Move>,
+    pub loads: Vec<u32>,
+    pub max_load: u32,
+}
+
+struct Item {
+    calories: u32,
+    owner: usize,
+}
+
+fn items(inventories: &[Vec<u32>]) -> Vec<Item> {
+    let mut items = inventories
+        .iter()
+        .enumerate()
+        .flat_map(|(owner, foods)| foods.iter().map(move |&calories| Item { calories, owner }))
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| Reverse(item.calories));
+    items
+}
+
+fn lower_bound(items: &[Item], elves: usize) -> u32 {
+    let total = items.iter().map(|item| item.calories).sum::<u32>();
+    let largest = items.first().map_or(0, |item| item.calories);
+    largest.max(total.div_ceil(elves as u32))
+}
+
+// Longest processing time first: give each item, largest first, to the
+// currently lightest elf.
+fn lpt(items: &[Item], elves: usize) -> Vec<usize> {
+    let mut loads = vec![0; elves];
+    items
+        .iter()
+        .map(|item| {
+            let (bin, _) = loads
+                .iter()
+                .enumerate()
+                .min_by_key(|&(bin, &load)| (load, bin))
+                .unwrap();
+            loads[bin] += item.calories;
+            bin
+        })
+        .collect()
+}
+
+struct Search<'a> {
+    items: &'a [Item],
+    lower_bound: u32,
+    loads: Vec<u32>,
+    assignment: Vec<usize>,
+    best: u32,
+    best_assignment: Vec<usize>,
+}
+
+impl Search<'_> {
+    fn branch(&mut self, i: usize, max_load: u32) {
+        if max_load >= self.best || self.best == self.lower_bound {
+            return;
+        }
+        if i == self.items.len() {
+            self.best = max_load;
+            self.best_assignment = self.assignment.clone();
+            return;
+        }
+        let calories = self.items[i].calories;
+        for bin in 0..self.loads.len() {
+            // Bins with equal loads are interchangeable, only try the first
+            if self.loads[..bin].contains(&self.loads[bin]) {
+                continue;
+            }
+            self.loads[bin] += calories;
+            self.assignment[i] = bin;
+            self.branch(i + 1, max_load.max(self.loads[bin]));
+            self.loads[bin] -= calories;
+        }
+    }
+}
+
+fn exact(items: &[Item], elves: usize) -> Vec<usize> {
+    let greedy = lpt(items, elves);
+    let mut search = Search {
+        items,
+        lower_bound: lower_bound(items, elves),
+        loads: vec![0; elves],
+        assignment: vec![0; items.len()],
+        best: max_load(items, &greedy, elves),
+        best_assignment: greedy,
+    };
+    search.branch(0, 0);
+    search.best_assignment
+}
+
+fn bin_loads(items: &[Item], assignment: &[usize], elves: usize) -> Vec<u32> {
+    let mut loads = vec![0; elves];
+    items
+        .iter()
+        .zip(assignment)
+        .for_each(|(item, &bin)| loads[bin] += item.calories);
+    loads
+}
+
+fn max_load(items: &[Item], assignment: &[usize], elves: usize) -> u32 {
+    bin_loads(items, assignment, elves)
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+}
+
+// Bins are anonymous, hand each one to the elf that already carries most of
+// its items so that as few items as possible have to move.
+fn bins_to_elves(items: &[Item], assignment: &[usize], elves: usize) -> Vec<usize> {
+    let mut shared = vec![vec![0; elves]; elves];
+    items
+        .iter()
+        .zip(assignment)
+        .for_each(|(item, &bin)| shared[bin][item.owner] += 1);
+    let mut pairs = (0..elves)
+        .flat_map(|bin| (0..elves).map(move |elf| (bin, elf)))
+        .collect::<Vec<_>>();
+    pairs.sort_by_key(|&(bin, elf)| Reverse(shared[bin][elf]));
+
+    let mut elf_of_bin = vec![None; elves];
+    let mut taken = vec![false; elves];
+    for (bin, elf) in pairs {
+        if elf_of_bin[bin].is_none() && !taken[elf] {
+            elf_of_bin[bin] = Some(elf);
+            taken[elf] = true;
+        }
+    }
+    elf_of_bin.into_iter().flatten().collect()
+}
+
+fn plan_with(inventories: &[Vec<u32>], solver: fn(&[Item], usize) -> Vec<usize>) -> Plan {
+    let elves = inventories.len();
+    let items = items(inventories);
+    if elves == 0 {
+        return Plan {
+            moves: Vec::new(),
+            loads: Vec::new(),
+            max_load: 0,
+        };
+    }
+    let bins = solver(&items, elves);
+    let elf_of_bin = bins_to_elves(&items, &bins, elves);
+    let assignment = bins
+        .into_iter()
+        .map(|bin| elf_of_bin[bin])
+        .collect::<Vec<_>>();
+
+    let moves = items
+        .iter()
+        .zip(&assignment)
+        .filter(|(item, &elf)| item.owner != elf)
+        .map(|(item, &elf)| Move {
+            calories: item.calories,
+            from: item.owner,
+            to: elf,
+        })
+        .collect();
+    let loads = bin_loads(&items, &assignment, elves);
+    Plan {
+        moves,
+        max_load: loads.iter().copied().max().unwrap_or(0),
+        loads,
+    }
+}
+
+pub fn plan_exact(inventories: &[Vec<u32>]) -> Plan {
+    plan_with(inventories, exact)
+}
+
+// Keep every elf at or under `target` as it is, take the largest items off
+// the others until they are under it too, then hand those out largest first
+// to the lightest elf.
+fn unload(inventories: &[Vec<u32>], target: u32) -> Plan {
+    let mut loads = inventories
+        .iter()
+        .map(|foods| foods.iter().sum())
+        .collect::<Vec<u32>>();
+    let mut taken = Vec::new();
+    for (elf, foods) in inventories.iter().enumerate() {
+        let mut foods = foods.clone();
+        foods.sort_unstable();
+        while loads[elf] > target {
+            let calories = foods.pop().unwrap();
+            loads[elf] -= calories;
+            taken.push(Item {
+                calories,
+                owner: elf,
+            });
+        }
+    }
+    taken.sort_by_key(|item| Reverse(item.calories));
+
+    let moves = taken
+        .iter()
+        .map(|item| {
+            let (elf, _) = loads
+                .iter()
+                .enumerate()
+                .min_by_key(|&(elf, &load)| (load, elf != item.owner, elf))
+                .unwrap();
+            loads[elf] += item.calories;
+            Move {
+                calories: item.calories,
+                from: item.owner,
+                to: elf,
+            }
+        })
+        .filter(|m| m.from != m.to)
+        .collect();
+    Plan {
+        moves,
+        max_load: loads.iter().copied().max().unwrap_or(0),
+        loads,
+    }
+}
+
+// LPT ignores who carries what, so also try to reach its maximum load by
+// moving items only off the elves above it, and keep whichever plan has the
+// lighter heaviest elf, then fewer moves.
+pub fn plan_greedy(inventories: &[Vec<u32>]) -> Plan {
+    let lpt = plan_with(inventories, lpt);
+    let unloaded = unload(inventories, lpt.max_load);
+    match (unloaded.max_load, unloaded.moves.len()) <= (lpt.max_load, lpt.moves.len()) {
+        true => unloaded,
+        false => lpt,
+    }
+}
+
+pub fn plan(inventories: &[Vec<u32>]) -> Plan {
+    let count = inventories.iter().map(Vec::len).sum::<usize>();
+    if count <= EXACT_LIMIT {
+        plan_exact(inventories)
+    } else {
+        plan_greedy(inventories)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(inventories: &[Vec<u32>], plan: &Plan) -> Vec<u32> {
+        let mut loads = inventories
+            .iter()
+            .map(|foods| foods.iter().sum())
+            .collect::<Vec<u32>>();
+        plan.moves.iter().for_each(|m| {
+            loads[m.from] -= m.calories;
+            loads[m.to] += m.calories;
+        });
+        loads
+    }
+
+    #[test]
+    fn test_exact() {
+        let inventories = vec![vec![8, 7, 6], vec![5, 4], vec![]];
+        let plan = plan_exact(&inventories);
+        assert_eq!(plan.max_load, 11);
+        assert_eq!(apply(&inventories, &plan), plan.loads);
+    }
+
+    #[test]
+    fn test_greedy() {
+        // LPT splits 3+2+2 | 3+2, the best split is 3+3 | 2+2+2
+        let inventories = vec![vec![3, 3, 2], vec![2, 2]];
+        let plan = plan_greedy(&inventories);
+        assert_eq!(plan.max_load, 7);
+        assert_eq!(apply(&inventories, &plan), plan.loads);
+        assert_eq!(plan_exact(&inventories).max_load, 6);
+    }
+
+    #[test]
+    fn test_balanced_needs_no_moves() {
+        let inventories = vec![vec![3, 2], vec![5], vec![1, 4]];
+        let plan = plan(&inventories);
+        assert_eq!(plan.max_load, 5);
+        assert!(plan.moves.is_empty());
+    }
+
+    #[test]
+    fn test_nearly_balanced() {
+        // One elf carries two items too many, LPT alone shuffles nearly all
+        let mut inventories = vec![vec![4, 4, 4]; 20];
+        inventories[7].extend([4, 4, 4]);
+        let plan = plan(&inventories);
+        assert_eq!(plan.max_load, 16);
+        assert_eq!(plan.moves.len(), 2);
+        assert!(plan.moves.iter().all(|m| m.from == 7));
+        assert_eq!(apply(&inventories, &plan), plan.loads);
+    }
+}
@@ -0,0 +1,216 @@
+use std::error::Error;
+
+use crate::{rules::RuleSet, symbols, Outcome, Strategy};
+
+// Most memory the sets of reachable totals may take
+const MEMORY_LIMIT: usize = 1 << 28;
+
+// A possible round: both symbols of the guide line and what it scores
+struct Line {
+    opponent: char,
+    response: char,
+    score: u32,
+}
+
+fn lines(rules: &RuleSet, strategy: Strategy, opponent: Option<usize>) -> Vec<Line> {
+    let opponents = match opponent {
+        Some(m) => vec![m],
+        None => (0..rules.len()).collect(),
+    };
+    opponents
+        .into_iter()
+        .flat_map(|opponent| {
+            let responses = match strategy {
+                Strategy::Moves => (0..rules.len())
+                    .map(|you| (rules.response_symbol(you), Some(you)))
+                    .collect::<Vec<_>>(),
+                Strategy::Outcomes => Outcome::ALL
+                    .iter()
+                    .map(|&outcome| (outcome.symbol(), rules.response_for(opponent, outcome)))
+                    .collect(),
+            };
+            responses.into_iter().filter_map(move |(response, you)| {
+                you.map(|you| Line {
+                    opponent: rules.opponent_symbol(opponent),
+                    response,
+                    score: rules.score(opponent, you),
+                })
+            })
+        })
+        .collect()
+}
+
+#[derive(Clone)]
+struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    fn new(bits: usize) -> Self {
+        BitSet {
+            words: vec![0; bits.div_ceil(64)],
+        }
+    }
+
+    fn contains(&self, bit: usize) -> bool {
+        self.words
+            .get(bit / 64)
+            .is_some_and(|word| word >> (bit % 64) & 1 == 1)
+    }
+
+    fn insert(&mut self, bit: usize) {
+        self.words[bit / 64] |= 1 << (bit % 64);
+    }
+
+    // self |= other << shift
+    fn or_shifted(&mut self, other: &BitSet, shift: usize) {
+        let (words, bits) = (shift / 64, shift % 64);
+        for i in (words..self.words.len()).rev() {
+            let source = i - words;
+            let mut word = other.words.get(source).map_or(0, |w| w << bits);
+            if bits > 0 && source > 0 {
+                word |= other.words[source - 1] >> (64 - bits);
+            }
+            self.words[i] |= word;
+        }
+    }
+}
+
+// Build a guide of `rounds` lines that scores exactly `target` when the second
+// column is read as `strategy` says. With `opponents` the first column is
+// fixed and only the second one is chosen.
+pub fn generate(
+    rules: &RuleSet,
+    strategy: Strategy,
+    rounds: usize,
+    target: u32,
+    opponents: Option<&[usize]>,
+) -> Result<String, Box<dyn Error>> {
+    if opponents.is_some_and(|opponents| opponents.len() != rounds) {
+        return Err("The opponent column must have one move per round".into());
+    }
+    let options = (0..rounds)
+        .map(|i| lines(rules, strategy, opponents.map(|opponents| opponents[i])))
+        .collect::<Vec<_>>();
+    let max = options
+        .iter()
+        .map(|lines| lines.iter().map(|line| line.score).max().unwrap_or(0))
+        .map(u64::from)
+        .sum::<u64>();
+    let impossible = || format!("No guide of {rounds} rounds scores {target}");
+    if u64::from(target) > max {
+        return Err(impossible().into());
+    }
+
+    // Sets of reachable totals are kept every `stride` rounds only, those in
+    // between are recomputed a stretch at a time while building the guide
+    // backwards
+    let bits = max as usize + 1;
+    let stride = rounds.isqrt().max(1);
+    let bytes = (rounds / stride + 1 + stride) * bits.div_ceil(64) * 8;
+    if bytes > MEMORY_LIMIT {
+        return Err(format!("A guide of {rounds} rounds scoring up to {max} is too large").into());
+    }
+    let advance = |previous: &BitSet, lines: &[Line]| {
+        let mut next = BitSet::new(bits);
+        lines
+            .iter()
+            .for_each(|line| next.or_shifted(previous, line.score as usize));
+        next
+    };
+    let mut reachable = BitSet::new(bits);
+    reachable.insert(0);
+    let mut checkpoints = vec![reachable.clone()];
+    for (i, lines) in options.iter().enumerate() {
+        reachable = advance(&reachable, lines);
+        if (i + 1) % stride == 0 {
+            checkpoints.push(reachable.clone());
+        }
+    }
+    if !reachable.contains(target as usize) {
+        return Err(impossible().into());
+    }
+
+    let mut remaining = target;
+    let mut guide = Vec::with_capacity(rounds);
+    for (checkpoint, first) in checkpoints.iter().zip((0..rounds).step_by(stride)).rev() {
+        // reachable[i - first]: every total score of the first i rounds
+        let last = (first + stride).min(rounds);
+        let mut reachable = vec![checkpoint.clone()];
+        for lines in &options[first..last - 1] {
+            reachable.push(advance(reachable.last().unwrap(), lines));
+        }
+        for i in (first..last).rev() {
+            let line = options[i]
+                .iter()
+                .find(|line| {
+                    line.score <= remaining
+                        && reachable[i - first].contains((remaining - line.score) as usize)
+                })
+                .expect("A reachable total has a predecessor");
+            remaining -= line.score;
+            guide.push(format!("{} {}", line.opponent, line.response));
+        }
+    }
+    guide.reverse();
+    Ok(guide.join("\n"))
+}
+
+// The opponent column of an existing guide, to keep it while generating
+pub fn opponent_column(rules: &RuleSet, data: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+    data.lines()
+        .map(|round| rules.opponent_move(symbols(round)?.0))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::score_guide;
+
+    #[test]
+    fn test_generate() {
+        let rules = RuleSet::classic();
+        for strategy in [Strategy::Moves, Strategy::Outcomes] {
+            for target in [100, 101, 457, 899, 900] {
+                let guide = generate(&rules, strategy, 100, target, None).unwrap();
+                assert_eq!(guide.lines().count(), 100);
+                assert_eq!(score_guide(&rules, &guide, strategy).unwrap(), target);
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_guides() {
+        let rules = RuleSet::classic();
+        let guide = generate(&rules, Strategy::Moves, 3000, 12_345, None).unwrap();
+        assert_eq!(guide.lines().count(), 3000);
+        assert_eq!(
+            score_guide(&rules, &guide, Strategy::Moves).unwrap(),
+            12_345
+        );
+
+        let huge = "outcomes 0 0 0\nmove Big A X 4000000000";
+        let error = generate(&huge.parse().unwrap(), Strategy::Moves, 1000, 0, None);
+        assert!(error.unwrap_err().to_string().ends_with("is too large"));
+    }
+
+    #[test]
+    fn test_impossible_targets() {
+        let rules = RuleSet::classic();
+        assert!(generate(&rules, Strategy::Moves, 2, 1, None).is_err());
+        assert!(generate(&rules, Strategy::Moves, 2, 19, None).is_err());
+        // Against rock the second column scores 3, 4 or 8
+        assert!(generate(&rules, Strategy::Outcomes, 1, 5, Some(&[0])).is_err());
+    }
+
+    #[test]
+    fn test_fixed_opponent_column() {
+        let rules = RuleSet::classic();
+        let opponents = opponent_column(&rules, "A Y\nB X\nC Z").unwrap();
+        let guide = generate(&rules, Strategy::Outcomes, 3, 20, Some(&opponents)).unwrap();
+        let column = guide.lines().map(|line| &line[..1]).collect::<String>();
+        assert_eq!(column, "ABC");
+        assert_eq!(score_guide(&rules, &guide, Strategy::Outcomes).unwrap(), 20);
+    }
+}
@@ -0,0 +1,120 @@
+use std::error::Error;
+
+use crate::{rules::RuleSet, score_interpretation, Interpretation, Outcome};
+
+// Every order of this many moves is still quick to score (8! = 40320)
+pub const MOVE_LIMIT: usize = 8;
+
+pub struct Candidate {
+    pub interpretation: Interpretation,
+    pub score: u32,
+}
+
+fn permutations<T: Copy>(items: &[T]) -> Vec<Vec<T>> {
+    if items.is_empty() {
+        return vec![Vec::new()];
+    }
+    (0..items.len())
+        .flat_map(|i| {
+            let mut rest = items.to_vec();
+            let first = rest.remove(i);
+            permutations(&rest).into_iter().map(move |mut permutation| {
+                permutation.insert(0, first);
+                permutation
+            })
+        })
+        .collect()
+}
+
+// Every way of reading the second column: each assignment of moves to the
+// response symbols and each assignment of outcomes to X, Y and Z.
+pub fn interpretations(rules: &RuleSet) -> Result<Vec<Interpretation>, Box<dyn Error>> {
+    if rules.len() > MOVE_LIMIT {
+        return Err(format!(
+            "Too many moves to try every reading: {}, at most {MOVE_LIMIT}",
+            rules.len()
+        )
+        .into());
+    }
+    let moves = (0..rules.len()).collect::<Vec<_>>();
+    Ok(permutations(&moves)
+        .into_iter()
+        .map(Interpretation::Moves)
+        .chain(
+            permutations(&Outcome::ALL)
+                .into_iter()
+                .map(|outcomes| Interpretation::Outcomes([outcomes[0], outcomes[1], outcomes[2]])),
+        )
+        .collect())
+}
+
+// All interpretations, best score first.
+pub fn rank(rules: &RuleSet, data: &str) -> Result<Vec<Candidate>, Box<dyn Error>> {
+    let mut candidates = interpretations(rules)?
+        .into_iter()
+        .map(|interpretation| {
+            Ok(Candidate {
+                score: score_interpretation(rules, data, &interpretation)?,
+                interpretation,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+    Ok(candidates)
+}
+
+pub fn matching(
+    rules: &RuleSet,
+    data: &str,
+    expected: u32,
+) -> Result<Vec<Candidate>, Box<dyn Error>> {
+    Ok(rank(rules, data)?
+        .into_iter()
+        .filter(|candidate| candidate.score == expected)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Strategy;
+
+    const DATA: &str = "A Y
+B X
+C Z";
+
+    #[test]
+    fn test_interpretations() {
+        assert_eq!(interpretations(&RuleSet::classic()).unwrap().len(), 6 + 6);
+        assert_eq!(interpretations(&RuleSet::rpsls()).unwrap().len(), 120 + 6);
+
+        let rules = (0..10)
+            .map(|i| format!("move M{i} {} {} 1", (b'A' + i) as char, (b'a' + i) as char))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .parse::<RuleSet>()
+            .unwrap();
+        let error = interpretations(&rules).unwrap_err().to_string();
+        assert_eq!(error, "Too many moves to try every reading: 10, at most 8");
+    }
+
+    #[test]
+    fn test_matching() {
+        let rules = RuleSet::classic();
+        let found = matching(&rules, DATA, 15).unwrap();
+        let part1 = Interpretation::new(&rules, Strategy::Moves);
+        assert!(found.iter().any(|c| c.interpretation == part1));
+
+        let found = matching(&rules, DATA, 12).unwrap();
+        let part2 = Interpretation::new(&rules, Strategy::Outcomes);
+        assert!(found.iter().any(|c| c.interpretation == part2));
+    }
+
+    #[test]
+    fn test_rank() {
+        let ranked = rank(&RuleSet::classic(), DATA).unwrap();
+        assert!(ranked.windows(2).all(|w| w[0].score >= w[1].score));
+        // Rock-Paper-Scissors all won: 8 + 9 + 7
+        assert_eq!(ranked[0].score, 24);
+    }
+}
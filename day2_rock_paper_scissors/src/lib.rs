@@ -1,4 +1,18 @@
-use std::{fs, ops::Add};
+use std::{
+    error::Error,
+    fmt::{self, Display},
+    fs,
+    str::FromStr,
+};
+
+pub mod generate;
+pub mod infer;
+pub mod nash;
+pub mod optimise;
+pub mod rules;
+pub mod tournament;
+
+use rules::RuleSet;
 
 pub fn data() -> String {
     fs::read_to_string("input.txt").unwrap().trim().to_string()
@@ -6,13 +20,15 @@ pub fn data() -> String {
 
 // --- Day 2: Rock Paper Scissors ---
 
-enum Move {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
     Rock = 1,
     Paper = 2,
     Scissors = 3,
 }
 
-enum Outcome {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
     Lose = 0,
     Draw = 3,
     Win = 6,
@@ -22,87 +38,364 @@ use Move::*;
 use Outcome::*;
 
 impl Move {
-    fn new(c: Option<char>) -> Move {
-        match c {
-            Some('A') | Some('X') => Rock,
-            Some('B') | Some('Y') => Paper,
-            Some('C') | Some('Z') => Scissors,
-            _ => unreachable!(),
+    pub const ALL: [Move; 3] = [Rock, Paper, Scissors];
+
+    pub fn beats(self) -> Move {
+        match self {
+            Rock => Scissors,
+            Paper => Rock,
+            Scissors => Paper,
         }
     }
+
+    pub fn opponent_symbol(self) -> char {
+        match self {
+            Rock => 'A',
+            Paper => 'B',
+            Scissors => 'C',
+        }
+    }
+
+    pub fn response_symbol(self) -> char {
+        match self {
+            Rock => 'X',
+            Paper => 'Y',
+            Scissors => 'Z',
+        }
+    }
+
+    pub fn score(self) -> u32 {
+        self as u32
+    }
 }
+
+impl TryFrom<char> for Move {
+    type Error = Box<dyn Error>;
+
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'A' | 'X' => Ok(Rock),
+            'B' | 'Y' => Ok(Paper),
+            'C' | 'Z' => Ok(Scissors),
+            _ => Err(format!("Unknown move {value}").into()),
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Rock" => Ok(Rock),
+            "Paper" => Ok(Paper),
+            "Scissors" => Ok(Scissors),
+            _ => match s.chars().collect::<Vec<_>>()[..] {
+                [c] => c.try_into(),
+                _ => Err(format!("Unknown move {s}").into()),
+            },
+        }
+    }
+}
+
+impl Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
 impl Outcome {
-    fn new(c: Option<char>) -> Outcome {
-        match c {
-            Some('X') => Lose,
-            Some('Y') => Draw,
-            Some('Z') => Win,
-            _ => unreachable!(),
+    pub const ALL: [Outcome; 3] = [Lose, Draw, Win];
+
+    pub const fn symbol(self) -> char {
+        match self {
+            Lose => 'X',
+            Draw => 'Y',
+            Win => 'Z',
         }
     }
+
+    pub fn score(self) -> u32 {
+        self as u32
+    }
 }
 
-impl Add<Outcome> for Move {
-    type Output = u32;
+impl TryFrom<char> for Outcome {
+    type Error = Box<dyn Error>;
 
-    fn add(self, other: Outcome) -> Self::Output {
-        self as u32 + other as u32
+    fn try_from(value: char) -> Result<Self, Self::Error> {
+        match value {
+            'X' => Ok(Lose),
+            'Y' => Ok(Draw),
+            'Z' => Ok(Win),
+            _ => Err(format!("Unknown outcome {value}").into()),
+        }
     }
 }
-impl Add<Move> for Outcome {
-    type Output = u32;
 
-    fn add(self, other: Move) -> Self::Output {
-        self as u32 + other as u32
+impl FromStr for Outcome {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Lose" => Ok(Lose),
+            "Draw" => Ok(Draw),
+            "Win" => Ok(Win),
+            _ => match s.chars().collect::<Vec<_>>()[..] {
+                [c] => c.try_into(),
+                _ => Err(format!("Unknown outcome {s}").into()),
+            },
+        }
     }
 }
 
-pub fn part1(data: &str) -> u32 {
-    data.split('\n')
-        .map(|round| -> u32 {
-            let mut round = round.chars();
-            let opponent = Move::new(round.next());
-            let you = Move::new(round.nth(1));
-            match (opponent, you) {
-                (Rock, Rock) => Rock + Draw,
-                (Rock, Paper) => Paper + Win,
-                (Rock, Scissors) => Scissors + Lose,
-
-                (Paper, Rock) => Rock + Lose,
-                (Paper, Paper) => Paper + Draw,
-                (Paper, Scissors) => Scissors + Win,
-
-                (Scissors, Rock) => Rock + Win,
-                (Scissors, Paper) => Paper + Lose,
-                (Scissors, Scissors) => Scissors + Draw,
-            }
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+// Symbols of the second column when it stands for an outcome
+const OUTCOME_SYMBOLS: [char; 3] = [Lose.symbol(), Draw.symbol(), Win.symbol()];
+
+// A round of the classic game, scoring under other rules goes through RuleSet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Move,
+    pub you: Move,
+}
+
+impl Round {
+    pub fn new(opponent: Move, you: Move) -> Self {
+        Round { opponent, you }
+    }
+
+    pub fn for_outcome(opponent: Move, outcome: Outcome) -> Self {
+        let you = Move::ALL
+            .into_iter()
+            .find(|&you| Round::new(opponent, you).outcome() == outcome)
+            .expect("Every outcome is reachable");
+        Round { opponent, you }
+    }
+
+    // Parse a guide line, reading the second column as `strategy` says
+    pub fn parse(s: &str, strategy: Strategy) -> Result<Self, Box<dyn Error>> {
+        let (opponent, response) = symbols(s)?;
+        let opponent = match opponent {
+            'A'..='C' => Move::try_from(opponent)?,
+            _ => return Err(format!("Unknown opponent move {opponent}").into()),
+        };
+        match strategy {
+            Strategy::Moves => match response {
+                'X'..='Z' => Ok(Round::new(opponent, response.try_into()?)),
+                _ => Err(format!("Unknown response move {response}").into()),
+            },
+            Strategy::Outcomes => Ok(Round::for_outcome(opponent, response.try_into()?)),
+        }
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        if self.you.beats() == self.opponent {
+            Win
+        } else if self.opponent.beats() == self.you {
+            Lose
+        } else {
+            Draw
+        }
+    }
+
+    pub fn score(&self) -> u32 {
+        self.you.score() + self.outcome().score()
+    }
+}
+
+impl FromStr for Round {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Round::parse(s, Strategy::Moves)
+    }
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}",
+            self.opponent.opponent_symbol(),
+            self.you.response_symbol()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundDetail {
+    pub line: usize,
+    // Indices of the moves played in the rule set
+    pub opponent: usize,
+    pub you: usize,
+    // The same round as classic moves, None under other rules
+    pub round: Option<Round>,
+    pub outcome: Outcome,
+    pub shape_score: u32,
+    pub outcome_score: u32,
+    pub total: u32,
+}
+
+impl RoundDetail {
+    pub fn describe(&self, rules: &RuleSet) -> String {
+        format!(
+            "{:>5}  {:<8} vs {:<8} {:<4}  {} + {} = {:<2}  total {}",
+            self.line,
+            rules.name(self.opponent),
+            rules.name(self.you),
+            self.outcome,
+            self.shape_score,
+            self.outcome_score,
+            self.shape_score + self.outcome_score,
+            self.total
+        )
+    }
+}
+
+// Score of every round of the guide under `rules`, with the running total
+pub fn breakdown(
+    rules: &RuleSet,
+    data: &str,
+    strategy: Strategy,
+) -> Result<Vec<RoundDetail>, Box<dyn Error>> {
+    let interpretation = Interpretation::new(rules, strategy);
+    // Classic rule indices follow the order of Move::ALL
+    let classic = *rules == RuleSet::classic();
+    let mut total = 0;
+    data.lines()
+        .enumerate()
+        .map(|(i, line)| -> Result<RoundDetail, Box<dyn Error>> {
+            let error = |e: Box<dyn Error>| format!("line {}: {e}", i + 1);
+            let (opponent, response) = symbols(line).map_err(error)?;
+            let opponent = rules.opponent_move(opponent).map_err(error)?;
+            let you = interpretation
+                .response(rules, opponent, response)
+                .map_err(error)?;
+            let outcome = rules.outcome(opponent, you);
+            total += rules.score(opponent, you);
+            Ok(RoundDetail {
+                line: i + 1,
+                opponent,
+                you,
+                round: classic.then(|| Round::new(Move::ALL[opponent], Move::ALL[you])),
+                outcome,
+                shape_score: rules.shape_score(you),
+                outcome_score: rules.outcome_score(outcome),
+                total,
+            })
         })
-        .sum()
+        .collect()
 }
 
-pub fn part2(data: &str) -> u32 {
-    data.split('\n')
-        .map(|round| -> u32 {
-            let mut round = round.chars();
-            let opponent = Move::new(round.next());
-            let outcome = Outcome::new(round.nth(1));
-            match (opponent, outcome) {
-                (Rock, Lose) => Scissors + Lose,
-                (Rock, Draw) => Rock + Draw,
-                (Rock, Win) => Paper + Win,
-
-                (Paper, Lose) => Rock + Lose,
-                (Paper, Draw) => Paper + Draw,
-                (Paper, Win) => Scissors + Win,
-
-                (Scissors, Lose) => Paper + Lose,
-                (Scissors, Draw) => Scissors + Draw,
-                (Scissors, Win) => Rock + Win,
+// What the second column of the strategy guide stands for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    Moves,
+    Outcomes,
+}
+
+// A concrete reading of the second column: either the move played for each
+// response symbol (in rule order), or the outcome wanted for X, Y and Z.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Interpretation {
+    Moves(Vec<usize>),
+    Outcomes([Outcome; 3]),
+}
+
+impl Interpretation {
+    pub fn new(rules: &RuleSet, strategy: Strategy) -> Self {
+        match strategy {
+            Strategy::Moves => Interpretation::Moves((0..rules.len()).collect()),
+            Strategy::Outcomes => Interpretation::Outcomes(Outcome::ALL),
+        }
+    }
+
+    pub(crate) fn response(
+        &self,
+        rules: &RuleSet,
+        opponent: usize,
+        symbol: char,
+    ) -> Result<usize, Box<dyn Error>> {
+        match self {
+            Interpretation::Moves(moves) => Ok(moves[rules.response_move(symbol)?]),
+            Interpretation::Outcomes(outcomes) => {
+                let outcome = OUTCOME_SYMBOLS
+                    .iter()
+                    .position(|&c| c == symbol)
+                    .map(|i| outcomes[i])
+                    .ok_or(format!("Unknown outcome {symbol}"))?;
+                rules.response_for(opponent, outcome).ok_or_else(|| {
+                    format!("No move can {outcome:?} against {}", rules.name(opponent)).into()
+                })
             }
+        }
+    }
+
+    pub fn describe(&self, rules: &RuleSet) -> String {
+        match self {
+            Interpretation::Moves(moves) => moves
+                .iter()
+                .enumerate()
+                .map(|(i, &m)| format!("{}={}", rules.response_symbol(i), rules.name(m)))
+                .collect::<Vec<_>>()
+                .join(" "),
+            Interpretation::Outcomes(outcomes) => OUTCOME_SYMBOLS
+                .iter()
+                .zip(outcomes)
+                .map(|(c, outcome)| format!("{c}={outcome:?}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+}
+
+fn symbols(round: &str) -> Result<(char, char), Box<dyn Error>> {
+    let mut symbols = round
+        .split_whitespace()
+        .map(|s| s.chars().collect::<Vec<_>>());
+    match (
+        symbols.next().as_deref(),
+        symbols.next().as_deref(),
+        symbols.next(),
+    ) {
+        (Some(&[opponent]), Some(&[response]), None) => Ok((opponent, response)),
+        _ => Err(format!("Error parsing round {round}").into()),
+    }
+}
+
+pub fn score_interpretation(
+    rules: &RuleSet,
+    data: &str,
+    interpretation: &Interpretation,
+) -> Result<u32, Box<dyn Error>> {
+    data.lines()
+        .map(|round| -> Result<u32, Box<dyn Error>> {
+            let (opponent, response) = symbols(round)?;
+            let opponent = rules.opponent_move(opponent)?;
+            let you = interpretation.response(rules, opponent, response)?;
+            Ok(rules.score(opponent, you))
         })
         .sum()
 }
 
+pub fn score_guide(rules: &RuleSet, data: &str, strategy: Strategy) -> Result<u32, Box<dyn Error>> {
+    score_interpretation(rules, data, &Interpretation::new(rules, strategy))
+}
+
+pub fn part1(data: &str) -> u32 {
+    score_guide(&RuleSet::classic(), data, Strategy::Moves).unwrap()
+}
+
+pub fn part2(data: &str) -> u32 {
+    score_guide(&RuleSet::classic(), data, Strategy::Outcomes).unwrap()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +414,53 @@ C Z";
         let result = part2(DATA);
         assert_eq!(result, 12);
     }
+    #[test]
+    fn test_typed_api() {
+        assert_eq!(Move::try_from('B').unwrap(), Paper);
+        assert_eq!("Scissors".parse::<Move>().unwrap(), Scissors);
+        assert_eq!(Paper.to_string().parse::<Move>().unwrap(), Paper);
+        assert_eq!("Z".parse::<Outcome>().unwrap(), Win);
+        assert!(Move::try_from('D').is_err());
+        assert!(Outcome::try_from('A').is_err());
+
+        let round = "C X".parse::<Round>().unwrap();
+        assert_eq!(round, Round::new(Scissors, Rock));
+        assert_eq!(round.outcome(), Win);
+        assert_eq!(round.to_string(), "C X");
+        assert_eq!(Round::parse("C X", Strategy::Outcomes).unwrap().you, Paper);
+        assert!("A".parse::<Round>().is_err());
+        assert!("X A".parse::<Round>().is_err());
+    }
+    #[test]
+    fn test_breakdown() {
+        let classic = RuleSet::classic();
+        let details = breakdown(&classic, DATA, Strategy::Outcomes).unwrap();
+        let totals = details.iter().map(|d| d.total).collect::<Vec<_>>();
+        assert_eq!(totals, vec![4, 5, 12]);
+        assert_eq!(details[2].round, Some(Round::new(Scissors, Rock)));
+        assert!(details[2].describe(&classic).contains("Scissors vs Rock"));
+        assert_eq!((details[2].shape_score, details[2].outcome_score), (1, 6));
+        assert!(breakdown(&classic, "A Y\nB Q", Strategy::Moves)
+            .unwrap_err()
+            .to_string()
+            .starts_with("line 2"));
+    }
+    #[test]
+    fn test_rpsls() {
+        let rules = RuleSet::rpsls();
+        let result = score_guide(&rules, "D Z\nE V\nA Y", Strategy::Moves).unwrap();
+        assert_eq!(result, 5 + 1 + 4);
+        let result = score_guide(&rules, "D Z\nE X", Strategy::Outcomes).unwrap();
+        assert_eq!(result, 7 + 1);
+
+        let details = breakdown(&rules, "D Z\nE V\nA Y", Strategy::Moves).unwrap();
+        assert_eq!(
+            (rules.name(details[0].opponent), rules.name(details[0].you)),
+            ("Lizard", "Spock")
+        );
+        assert_eq!(details[0].round, None);
+        assert_eq!(details[0].outcome, Lose);
+        assert_eq!((details[0].shape_score, details[0].outcome_score), (5, 0));
+        assert_eq!(details[2].total, 5 + 1 + 4);
+    }
 }
@@ -1,6 +1,137 @@
-use day2_rock_paper_scissors::*;
+use std::{env, error::Error};
 
-fn main() {
-    println!("Part 1: {}", part1(&data()));
-    println!("Part 2: {}", part2(&data()));
+use day2_rock_paper_scissors::{
+    generate, infer, nash, optimise,
+    rules::RuleSet,
+    tournament::{
+        self, FrequencyCounter, GuideReplay, PatternMatcher, Player, Random, WinStayLoseShift,
+    },
+    *,
+};
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut rules = RuleSet::classic();
+    let mut infer = None;
+    let mut rank = false;
+    let mut optimise = false;
+    let mut nash = false;
+    let mut tournament = false;
+    let mut breakdown = None;
+    let mut generate = None;
+    let mut keep_opponent = false;
+    let mut rounds = 1000;
+    let mut seed = 2022;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--infer" => infer = Some(args.next().ok_or("Missing expected score")?.parse()?),
+            "--rank" => rank = true,
+            "--optimise" => optimise = true,
+            "--nash" => nash = true,
+            "--tournament" => tournament = true,
+            "--generate" => {
+                let rounds = args.next().ok_or("Missing number of rounds")?.parse()?;
+                let target = args.next().ok_or("Missing target score")?.parse()?;
+                let strategy = match args.next().as_deref() {
+                    Some("1") => Strategy::Moves,
+                    Some("2") => Strategy::Outcomes,
+                    _ => return Err("Expected part 1 or 2".into()),
+                };
+                generate = Some((rounds, target, strategy));
+            }
+            "--keep-opponent" => keep_opponent = true,
+            "--breakdown" => {
+                breakdown = Some(match args.next().as_deref() {
+                    Some("1") => Strategy::Moves,
+                    Some("2") => Strategy::Outcomes,
+                    _ => return Err("Expected part 1 or 2".into()),
+                })
+            }
+            "--rounds" => rounds = args.next().ok_or("Missing number of rounds")?.parse()?,
+            "--seed" => seed = args.next().ok_or("Missing seed")?.parse()?,
+            "classic" => rules = RuleSet::classic(),
+            "rpsls" => rules = RuleSet::rpsls(),
+            path => rules = RuleSet::from_file(path)?,
+        }
+    }
+
+    if let Some((rounds, target, strategy)) = generate {
+        let opponents = match keep_opponent {
+            true => Some(generate::opponent_column(&rules, &data())?),
+            false => None,
+        };
+        let guide = generate::generate(&rules, strategy, rounds, target, opponents.as_deref())?;
+        println!("{guide}");
+        return Ok(());
+    }
+    if let Some(strategy) = breakdown {
+        for detail in day2_rock_paper_scissors::breakdown(&rules, &data(), strategy)? {
+            println!("{}", detail.describe(&rules));
+        }
+        return Ok(());
+    }
+    if let Some(expected) = infer {
+        for candidate in infer::matching(&rules, &data(), expected)? {
+            println!("{}", candidate.interpretation.describe(&rules));
+        }
+        return Ok(());
+    }
+    if rank {
+        for candidate in infer::rank(&rules, &data())? {
+            println!(
+                "{:>6}  {}",
+                candidate.score,
+                candidate.interpretation.describe(&rules)
+            );
+        }
+        return Ok(());
+    }
+
+    if optimise {
+        let result = optimise::optimise(&rules, &data(), Strategy::Moves)?;
+        println!("{}", result.guide(&rules));
+        println!("Expected score: {:.1}", result.expected_score);
+        println!("Achieved score: {}", result.achieved_score);
+        println!("Guide score: {}", result.guide_score);
+        return Ok(());
+    }
+
+    if nash {
+        let equilibrium = nash::equilibrium(&rules);
+        for (m, (you, opponent)) in equilibrium
+            .you
+            .iter()
+            .zip(&equilibrium.opponent)
+            .enumerate()
+        {
+            println!(
+                "{:>10}  you {you:.4}  opponent {opponent:.4}",
+                rules.name(m)
+            );
+        }
+        println!("Game value: {:.4}", equilibrium.value);
+        return Ok(());
+    }
+
+    if tournament {
+        let mut players: Vec<Box<dyn Player>> = vec![
+            Box::new(GuideReplay::new(&rules, &data(), Strategy::Moves)?),
+            Box::new(Random),
+            Box::new(FrequencyCounter::default()),
+            Box::new(PatternMatcher::new(3)),
+            Box::new(WinStayLoseShift::default()),
+        ];
+        print!(
+            "{}",
+            tournament::round_robin(&rules, &mut players, rounds, seed)
+        );
+        return Ok(());
+    }
+
+    println!("Part 1: {}", score_guide(&rules, &data(), Strategy::Moves)?);
+    println!(
+        "Part 2: {}",
+        score_guide(&rules, &data(), Strategy::Outcomes)?
+    );
+    Ok(())
 }
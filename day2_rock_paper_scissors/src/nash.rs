@@ -0,0 +1,184 @@
+use crate::rules::RuleSet;
+
+const EPSILON: f64 = 1e-9;
+
+// Mixed strategies of the zero-sum game where we try to maximise our score
+// and the opponent tries to minimise it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Equilibrium {
+    pub you: Vec<f64>,
+    pub opponent: Vec<f64>,
+    pub value: f64,
+}
+
+// payoff[you][opponent]: our score for the round
+pub fn payoff_matrix(rules: &RuleSet) -> Vec<Vec<f64>> {
+    (0..rules.len())
+        .map(|you| {
+            (0..rules.len())
+                .map(|opponent| f64::from(rules.score(opponent, you)))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn equilibrium(rules: &RuleSet) -> Equilibrium {
+    solve(&payoff_matrix(rules))
+}
+
+// Shift the payoffs so they are all positive, then solve
+//
+//   maximise sum(w) subject to payoff * w <= 1, w >= 0
+//
+// with the simplex method. The game value is 1 / sum(w), w scaled by the
+// value is the opponent strategy and the duals scaled by it are ours.
+pub fn solve(payoff: &[Vec<f64>]) -> Equilibrium {
+    let rows = payoff.len();
+    let columns = payoff.first().map_or(0, Vec::len);
+    assert!(rows > 0 && columns > 0, "Empty payoff matrix");
+
+    let min = payoff.iter().flatten().copied().fold(f64::MAX, f64::min);
+    let shift = 1.0 - min;
+
+    // Tableau: constraint rows, then the objective row. Columns are the
+    // opponent weights, one slack per row and the right hand side.
+    let width = columns + rows + 1;
+    let mut tableau = payoff
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            let mut line = vec![0.0; width];
+            row.iter()
+                .enumerate()
+                .for_each(|(j, &p)| line[j] = p + shift);
+            line[columns + i] = 1.0;
+            line[width - 1] = 1.0;
+            line
+        })
+        .collect::<Vec<_>>();
+    let mut objective = vec![0.0; width];
+    objective[..columns].iter_mut().for_each(|c| *c = -1.0);
+    tableau.push(objective);
+    let mut basis = (columns..columns + rows).collect::<Vec<_>>();
+
+    // Bland's rule: lowest entering and leaving indices, so it never cycles
+    while let Some(entering) = (0..width - 1).find(|&j| tableau[rows][j] < -EPSILON) {
+        let leaving = (0..rows)
+            .filter(|&i| tableau[i][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio_a = tableau[a][width - 1] / tableau[a][entering];
+                let ratio_b = tableau[b][width - 1] / tableau[b][entering];
+                ratio_a
+                    .partial_cmp(&ratio_b)
+                    .unwrap()
+                    .then(basis[a].cmp(&basis[b]))
+            })
+            .expect("The game LP is bounded");
+        pivot(&mut tableau, leaving, entering);
+        basis[leaving] = entering;
+    }
+
+    let total = tableau[rows][width - 1];
+    let value = 1.0 / total;
+    let mut opponent = vec![0.0; columns];
+    basis
+        .iter()
+        .enumerate()
+        .filter(|&(_, &j)| j < columns)
+        .for_each(|(i, &j)| opponent[j] = tableau[i][width - 1] * value);
+    let you = (0..rows)
+        .map(|i| tableau[rows][columns + i] * value)
+        .collect();
+
+    Equilibrium {
+        you,
+        opponent,
+        value: value - shift,
+    }
+}
+
+fn pivot(tableau: &mut [Vec<f64>], row: usize, column: usize) {
+    let divisor = tableau[row][column];
+    tableau[row].iter_mut().for_each(|x| *x /= divisor);
+    let pivot_row = tableau[row].clone();
+    for (i, line) in tableau.iter_mut().enumerate() {
+        let factor = line[column];
+        if i != row && factor != 0.0 {
+            line.iter_mut()
+                .zip(&pivot_row)
+                .for_each(|(x, p)| *x -= factor * p);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{a} != {b}");
+    }
+
+    fn assert_all_close(a: &[f64], b: &[f64]) {
+        assert_eq!(a.len(), b.len());
+        a.iter().zip(b).for_each(|(&a, &b)| assert_close(a, b));
+    }
+
+    #[test]
+    fn test_two_by_two() {
+        // [[a, b], [c, d]] without a saddle point: p = (d - c) / (a - b - c + d),
+        // q = (d - b) / (a - b - c + d), v = (ad - bc) / (a - b - c + d)
+        let result = solve(&[vec![3.0, -1.0], vec![-2.0, 1.0]]);
+        assert_all_close(&result.you, &[3.0 / 7.0, 4.0 / 7.0]);
+        assert_all_close(&result.opponent, &[2.0 / 7.0, 5.0 / 7.0]);
+        assert_close(result.value, 1.0 / 7.0);
+    }
+
+    #[test]
+    fn test_saddle_point() {
+        let result = solve(&[vec![4.0, 5.0], vec![2.0, 1.0]]);
+        assert_all_close(&result.you, &[1.0, 0.0]);
+        assert_all_close(&result.opponent, &[1.0, 0.0]);
+        assert_close(result.value, 4.0);
+    }
+
+    #[test]
+    fn test_symmetric_games() {
+        // Lose 0, draw 1, win 2 and no shape score: uniform play, value 1
+        let rules = "outcomes 0 1 2
+move Rock A X 0
+move Paper B Y 0
+move Scissors C Z 0
+Rock beats Scissors
+Paper beats Rock
+Scissors beats Paper"
+            .parse::<RuleSet>()
+            .unwrap();
+        let result = equilibrium(&rules);
+        assert_all_close(&result.you, &[1.0 / 3.0; 3]);
+        assert_all_close(&result.opponent, &[1.0 / 3.0; 3]);
+        assert_close(result.value, 1.0);
+    }
+
+    #[test]
+    fn test_classic_rules() {
+        let payoff = payoff_matrix(&RuleSet::classic());
+        let result = solve(&payoff);
+        // Neither side can do better by switching to a pure strategy
+        (0..3).for_each(|opponent| {
+            let score = (0..3)
+                .map(|you| result.you[you] * payoff[you][opponent])
+                .sum::<f64>();
+            assert!(score >= result.value - 1e-6);
+        });
+        (0..3).for_each(|you| {
+            let score = (0..3)
+                .map(|opponent| result.opponent[opponent] * payoff[you][opponent])
+                .sum::<f64>();
+            assert!(score <= result.value + 1e-6);
+        });
+        assert_all_close(&result.you, &[1.0 / 3.0; 3]);
+        assert_all_close(&result.opponent, &[4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0]);
+        assert_close(result.value, 5.0);
+    }
+}
@@ -0,0 +1,144 @@
+use std::error::Error;
+
+use crate::{rules::RuleSet, score_guide, symbols, Strategy};
+
+pub struct Optimisation {
+    pub opponents: Vec<usize>,
+    // Share of rounds in which the opponent played each move
+    pub distribution: Vec<f64>,
+    // transitions[a][b]: how often the opponent followed move a with move b
+    pub transitions: Vec<Vec<f64>>,
+    pub responses: Vec<usize>,
+    pub expected_score: f64,
+    pub achieved_score: u32,
+    pub guide_score: u32,
+}
+
+impl Optimisation {
+    // The guide rewritten with the best responses in the second column
+    pub fn guide(&self, rules: &RuleSet) -> String {
+        self.opponents
+            .iter()
+            .zip(&self.responses)
+            .map(|(&opponent, &you)| {
+                format!(
+                    "{} {}",
+                    rules.opponent_symbol(opponent),
+                    rules.response_symbol(you)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn normalise(counts: &[u32]) -> Option<Vec<f64>> {
+    let total = counts.iter().sum::<u32>();
+    (total > 0).then(|| {
+        counts
+            .iter()
+            .map(|&count| f64::from(count) / f64::from(total))
+            .collect()
+    })
+}
+
+fn expected_score(rules: &RuleSet, distribution: &[f64], you: usize) -> f64 {
+    distribution
+        .iter()
+        .enumerate()
+        .map(|(opponent, p)| p * f64::from(rules.score(opponent, you)))
+        .sum()
+}
+
+// The response with the highest expected score, the first one on ties.
+pub(crate) fn best_response(rules: &RuleSet, distribution: &[f64]) -> (usize, f64) {
+    (0..rules.len())
+        .map(|you| (you, expected_score(rules, distribution, you)))
+        .fold((0, f64::MIN), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+pub fn optimise(
+    rules: &RuleSet,
+    data: &str,
+    strategy: Strategy,
+) -> Result<Optimisation, Box<dyn Error>> {
+    let opponents = data
+        .lines()
+        .map(|round| rules.opponent_move(symbols(round)?.0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut counts = vec![0; rules.len()];
+    let mut transition_counts = vec![vec![0; rules.len()]; rules.len()];
+    opponents.iter().for_each(|&m| counts[m] += 1);
+    opponents
+        .windows(2)
+        .for_each(|w| transition_counts[w[0]][w[1]] += 1);
+    let distribution = normalise(&counts).unwrap_or_else(|| vec![0.0; rules.len()]);
+    // Moves never followed by anything fall back to the overall distribution
+    let transitions = transition_counts
+        .iter()
+        .map(|row| normalise(row).unwrap_or_else(|| distribution.clone()))
+        .collect::<Vec<_>>();
+
+    let mut expected = 0.0;
+    let responses = opponents
+        .iter()
+        .enumerate()
+        .map(|(i, _)| {
+            let prediction = match i {
+                0 => &distribution,
+                _ => &transitions[opponents[i - 1]],
+            };
+            let (you, score) = best_response(rules, prediction);
+            expected += score;
+            you
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Optimisation {
+        achieved_score: opponents
+            .iter()
+            .zip(&responses)
+            .map(|(&opponent, &you)| rules.score(opponent, you))
+            .sum(),
+        guide_score: score_guide(rules, data, strategy)?,
+        expected_score: expected,
+        opponents,
+        distribution,
+        transitions,
+        responses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_opponent() {
+        let rules = RuleSet::classic();
+        let result = optimise(&rules, "A X\nA X\nA X", Strategy::Moves).unwrap();
+        assert_eq!(result.distribution, vec![1.0, 0.0, 0.0]);
+        assert_eq!(result.responses, vec![1, 1, 1]);
+        assert_eq!(result.achieved_score, 24);
+        assert_eq!(result.guide_score, 12);
+        assert_eq!(result.guide(&rules), "A Y\nA Y\nA Y");
+    }
+
+    #[test]
+    fn test_markov_opponent() {
+        let rules = RuleSet::classic();
+        let result = optimise(&rules, "A X\nB X\nA X\nB X", Strategy::Moves).unwrap();
+        assert_eq!(result.transitions[0], vec![0.0, 1.0, 0.0]);
+        assert_eq!(result.transitions[1], vec![1.0, 0.0, 0.0]);
+        assert_eq!(result.responses, vec![1, 2, 1, 2]);
+        assert_eq!(result.achieved_score, 34);
+        assert_eq!(result.expected_score, 32.5);
+    }
+}
@@ -0,0 +1,275 @@
+use std::{error::Error, fs, str::FromStr};
+
+use crate::{Move, Outcome};
+
+// Rule files are line based, blank lines and `#` comments are ignored:
+//
+//   outcomes <lose> <draw> <win>
+//   move <name> <opponent symbol> <response symbol> <shape score>
+//   <name> beats <name>...
+//
+// Two moves where neither beats the other are a draw.
+const RPSLS: &str = "# Rock Paper Scissors Lizard Spock
+outcomes 0 3 6
+move Rock A V 1
+move Paper B W 2
+move Scissors C X 3
+move Lizard D Y 4
+move Spock E Z 5
+Rock beats Scissors Lizard
+Paper beats Rock Spock
+Scissors beats Paper Lizard
+Lizard beats Paper Spock
+Spock beats Rock Scissors";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MoveRule {
+    name: String,
+    opponent: char,
+    response: char,
+    score: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    moves: Vec<MoveRule>,
+    beats: Vec<Vec<bool>>,
+    outcome_scores: [u32; 3],
+}
+
+impl RuleSet {
+    fn new(outcome_scores: [u32; 3]) -> Self {
+        RuleSet {
+            moves: Vec::new(),
+            beats: Vec::new(),
+            outcome_scores,
+        }
+    }
+
+    fn add_move(
+        &mut self,
+        name: &str,
+        opponent: char,
+        response: char,
+        score: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        if self.index(name).is_some() {
+            return Err(format!("Duplicated move {name}").into());
+        }
+        if self.moves.iter().any(|m| m.opponent == opponent) {
+            return Err(format!("Duplicated opponent symbol {opponent}").into());
+        }
+        if self.moves.iter().any(|m| m.response == response) {
+            return Err(format!("Duplicated response symbol {response}").into());
+        }
+        self.moves.push(MoveRule {
+            name: name.to_string(),
+            opponent,
+            response,
+            score,
+        });
+        self.beats.iter_mut().for_each(|row| row.push(false));
+        self.beats.push(vec![false; self.moves.len()]);
+        Ok(())
+    }
+
+    fn add_beats(&mut self, winner: &str, loser: &str) -> Result<(), Box<dyn Error>> {
+        let winner_index = self.index(winner).ok_or(format!("Unknown move {winner}"))?;
+        let loser_index = self.index(loser).ok_or(format!("Unknown move {loser}"))?;
+        if winner_index == loser_index {
+            return Err(format!("{winner} cannot beat itself").into());
+        }
+        if self.beats[loser_index][winner_index] {
+            return Err(format!("{winner} and {loser} beat each other").into());
+        }
+        self.beats[winner_index][loser_index] = true;
+        Ok(())
+    }
+
+    fn index(&self, name: &str) -> Option<usize> {
+        self.moves.iter().position(|m| m.name == name)
+    }
+
+    pub fn classic() -> Self {
+        let mut rules = RuleSet::new(Outcome::ALL.map(Outcome::score));
+        for m in Move::ALL {
+            let (opponent, response) = (m.opponent_symbol(), m.response_symbol());
+            rules
+                .add_move(&m.to_string(), opponent, response, m.score())
+                .expect("Classic moves are unique");
+        }
+        for m in Move::ALL {
+            rules
+                .add_beats(&m.to_string(), &m.beats().to_string())
+                .expect("Classic rules are consistent");
+        }
+        rules
+    }
+
+    pub fn rpsls() -> Self {
+        RPSLS.parse().expect("Built-in rules are valid")
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    pub fn name(&self, m: usize) -> &str {
+        &self.moves[m].name
+    }
+
+    pub fn opponent_symbol(&self, m: usize) -> char {
+        self.moves[m].opponent
+    }
+
+    pub fn response_symbol(&self, m: usize) -> char {
+        self.moves[m].response
+    }
+
+    pub fn opponent_move(&self, symbol: char) -> Result<usize, Box<dyn Error>> {
+        self.moves
+            .iter()
+            .position(|m| m.opponent == symbol)
+            .ok_or_else(|| format!("Unknown opponent move {symbol}").into())
+    }
+
+    pub fn response_move(&self, symbol: char) -> Result<usize, Box<dyn Error>> {
+        self.moves
+            .iter()
+            .position(|m| m.response == symbol)
+            .ok_or_else(|| format!("Unknown response move {symbol}").into())
+    }
+
+    pub fn shape_score(&self, m: usize) -> u32 {
+        self.moves[m].score
+    }
+
+    pub fn outcome_score(&self, outcome: Outcome) -> u32 {
+        match outcome {
+            Outcome::Lose => self.outcome_scores[0],
+            Outcome::Draw => self.outcome_scores[1],
+            Outcome::Win => self.outcome_scores[2],
+        }
+    }
+
+    pub fn outcome(&self, opponent: usize, you: usize) -> Outcome {
+        if self.beats[you][opponent] {
+            Outcome::Win
+        } else if self.beats[opponent][you] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn score(&self, opponent: usize, you: usize) -> u32 {
+        self.shape_score(you) + self.outcome_score(self.outcome(opponent, you))
+    }
+
+    // The first move, in rule order, that ends the round with `outcome`.
+    pub fn response_for(&self, opponent: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.len()).find(|&you| self.outcome(opponent, you) == outcome)
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut rules = RuleSet::new([0, 3, 6]);
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default();
+            let words = line.split_whitespace().collect::<Vec<_>>();
+            let error = |message: &str| format!("line {}: {message}", i + 1);
+            match words.as_slice() {
+                [] => {}
+                ["outcomes", scores @ ..] => {
+                    let scores = scores
+                        .iter()
+                        .map(|score| score.parse::<u32>())
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| error(&e.to_string()))?;
+                    rules.outcome_scores = scores
+                        .try_into()
+                        .map_err(|_| error("expected three outcome scores"))?;
+                }
+                ["move", name, opponent, response, score] => {
+                    let symbol = |s: &str| match s.chars().collect::<Vec<_>>()[..] {
+                        [c] => Ok(c),
+                        _ => Err(error(&format!("invalid symbol {s}"))),
+                    };
+                    let score = score.parse().map_err(|_| error("invalid shape score"))?;
+                    rules
+                        .add_move(name, symbol(opponent)?, symbol(response)?, score)
+                        .map_err(|e| error(&e.to_string()))?;
+                }
+                [winner, "beats", losers @ ..] if !losers.is_empty() => {
+                    for loser in losers {
+                        rules
+                            .add_beats(winner, loser)
+                            .map_err(|e| error(&e.to_string()))?;
+                    }
+                }
+                _ => return Err(error("expected `outcomes`, `move` or `beats` rule").into()),
+            }
+        }
+        if rules.is_empty() {
+            return Err("Rule set has no moves".into());
+        }
+        Ok(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classic() {
+        let rules = RuleSet::classic();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules.score(0, 1), 8);
+        assert_eq!(rules.score(1, 0), 1);
+        assert_eq!(rules.score(2, 2), 6);
+        assert_eq!(rules.response_for(0, Outcome::Lose), Some(2));
+    }
+
+    #[test]
+    fn test_rpsls() {
+        let rules = RuleSet::rpsls();
+        let spock = rules.response_move('Z').unwrap();
+        let lizard = rules.opponent_move('D').unwrap();
+        assert_eq!(rules.name(spock), "Spock");
+        assert_eq!(rules.outcome(lizard, spock), Outcome::Lose);
+        assert_eq!(rules.score(lizard, spock), 5);
+    }
+
+    #[test]
+    fn test_custom_rules() {
+        let rules = "outcomes 0 1 2
+move Fire F A 0
+move Water W B 0
+Water beats Fire"
+            .parse::<RuleSet>()
+            .unwrap();
+        assert_eq!(rules.score(0, 1), 2);
+        assert_eq!(rules.score(1, 0), 0);
+        assert_eq!(rules.score(0, 0), 1);
+
+        assert!("move Rock A X 1\nRock beats Rock"
+            .parse::<RuleSet>()
+            .is_err());
+        assert!("move Rock A X 1\nRock beats Paper"
+            .parse::<RuleSet>()
+            .is_err());
+        assert!("outcomes 0 3".parse::<RuleSet>().is_err());
+    }
+}
@@ -0,0 +1,379 @@
+use std::{error::Error, fmt::Display};
+
+use crate::{optimise::best_response, rules::RuleSet, symbols, Interpretation, Outcome, Strategy};
+
+// SplitMix64, small and good enough to make tournaments reproducible
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+pub trait Player {
+    fn name(&self) -> String;
+    fn choose(&mut self, rules: &RuleSet, rng: &mut Rng) -> usize;
+    fn observe(&mut self, _rules: &RuleSet, _mine: usize, _theirs: usize) {}
+    // Forget everything learnt, called before each match
+    fn reset(&mut self) {}
+}
+
+// Plays the moves recommended by a strategy guide, over and over
+pub struct GuideReplay {
+    moves: Vec<usize>,
+    round: usize,
+}
+
+impl GuideReplay {
+    pub fn new(rules: &RuleSet, data: &str, strategy: Strategy) -> Result<Self, Box<dyn Error>> {
+        let interpretation = Interpretation::new(rules, strategy);
+        let moves = data
+            .lines()
+            .map(|round| {
+                let (opponent, response) = symbols(round)?;
+                interpretation.response(rules, rules.opponent_move(opponent)?, response)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if moves.is_empty() {
+            return Err("Empty strategy guide".into());
+        }
+        Ok(GuideReplay { moves, round: 0 })
+    }
+}
+
+impl Player for GuideReplay {
+    fn name(&self) -> String {
+        "guide".to_string()
+    }
+
+    fn choose(&mut self, _rules: &RuleSet, _rng: &mut Rng) -> usize {
+        let m = self.moves[self.round % self.moves.len()];
+        self.round += 1;
+        m
+    }
+
+    fn reset(&mut self) {
+        self.round = 0;
+    }
+}
+
+pub struct Random;
+
+impl Player for Random {
+    fn name(&self) -> String {
+        "random".to_string()
+    }
+
+    fn choose(&mut self, rules: &RuleSet, rng: &mut Rng) -> usize {
+        rng.below(rules.len())
+    }
+}
+
+// Best response to how often the opponent played each move so far
+#[derive(Default)]
+pub struct FrequencyCounter {
+    counts: Vec<u32>,
+}
+
+impl Player for FrequencyCounter {
+    fn name(&self) -> String {
+        "frequency".to_string()
+    }
+
+    fn choose(&mut self, rules: &RuleSet, rng: &mut Rng) -> usize {
+        let total = self.counts.iter().sum::<u32>();
+        if total == 0 {
+            return rng.below(rules.len());
+        }
+        let distribution = self
+            .counts
+            .iter()
+            .map(|&count| f64::from(count) / f64::from(total))
+            .collect::<Vec<_>>();
+        best_response(rules, &distribution).0
+    }
+
+    fn observe(&mut self, rules: &RuleSet, _mine: usize, theirs: usize) {
+        self.counts.resize(rules.len(), 0);
+        self.counts[theirs] += 1;
+    }
+
+    fn reset(&mut self) {
+        self.counts.clear();
+    }
+}
+
+// Looks for the latest opponent moves earlier in the match and expects the
+// move that followed them most often.
+pub struct PatternMatcher {
+    length: usize,
+    history: Vec<usize>,
+}
+
+impl PatternMatcher {
+    pub fn new(length: usize) -> Self {
+        PatternMatcher {
+            length,
+            history: Vec::new(),
+        }
+    }
+}
+
+impl Player for PatternMatcher {
+    fn name(&self) -> String {
+        format!("pattern-{}", self.length)
+    }
+
+    fn choose(&mut self, rules: &RuleSet, rng: &mut Rng) -> usize {
+        if self.history.len() <= self.length {
+            return rng.below(rules.len());
+        }
+        let pattern = &self.history[self.history.len() - self.length..];
+        let mut counts = vec![0; rules.len()];
+        self.history
+            .windows(self.length + 1)
+            .filter(|window| &window[..self.length] == pattern)
+            .for_each(|window| counts[window[self.length]] += 1);
+        let total = counts.iter().sum::<u32>();
+        if total == 0 {
+            return rng.below(rules.len());
+        }
+        let distribution = counts
+            .iter()
+            .map(|&count| f64::from(count) / f64::from(total))
+            .collect::<Vec<_>>();
+        best_response(rules, &distribution).0
+    }
+
+    fn observe(&mut self, _rules: &RuleSet, _mine: usize, theirs: usize) {
+        self.history.push(theirs);
+    }
+
+    fn reset(&mut self) {
+        self.history.clear();
+    }
+}
+
+// Keeps a winning move, otherwise switches to what beats the opponent's
+// last move.
+#[derive(Default)]
+pub struct WinStayLoseShift {
+    last: Option<(usize, usize)>,
+}
+
+impl Player for WinStayLoseShift {
+    fn name(&self) -> String {
+        "win-stay-lose-shift".to_string()
+    }
+
+    fn choose(&mut self, rules: &RuleSet, rng: &mut Rng) -> usize {
+        match self.last {
+            None => rng.below(rules.len()),
+            Some((mine, theirs)) if rules.outcome(theirs, mine) == Outcome::Win => mine,
+            Some((mine, theirs)) => rules.response_for(theirs, Outcome::Win).unwrap_or(mine),
+        }
+    }
+
+    fn observe(&mut self, _rules: &RuleSet, mine: usize, theirs: usize) {
+        self.last = Some((mine, theirs));
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub scores: [u32; 2],
+    pub wins: [u32; 2],
+    pub draws: u32,
+}
+
+pub fn play_match(
+    rules: &RuleSet,
+    players: [&mut dyn Player; 2],
+    rounds: usize,
+    rng: &mut Rng,
+) -> MatchResult {
+    let [a, b] = players;
+    a.reset();
+    b.reset();
+    let mut result = MatchResult::default();
+    for _ in 0..rounds {
+        let (move_a, move_b) = (a.choose(rules, rng), b.choose(rules, rng));
+        result.scores[0] += rules.score(move_b, move_a);
+        result.scores[1] += rules.score(move_a, move_b);
+        match rules.outcome(move_b, move_a) {
+            Outcome::Win => result.wins[0] += 1,
+            Outcome::Lose => result.wins[1] += 1,
+            Outcome::Draw => result.draws += 1,
+        }
+        a.observe(rules, move_a, move_b);
+        b.observe(rules, move_b, move_a);
+    }
+    result
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub score: u32,
+    pub matches: u32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Standing {
+    pub fn win_rate(&self) -> f64 {
+        match self.matches {
+            0 => 0.0,
+            matches => f64::from(self.wins) / f64::from(matches),
+        }
+    }
+}
+
+pub struct Leaderboard {
+    pub standings: Vec<Standing>,
+}
+
+impl Display for Leaderboard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<4}{:<22}{:>10}{:>6}{:>6}{:>6}{:>10}",
+            "#", "player", "score", "won", "drawn", "lost", "win rate"
+        )?;
+        for (i, s) in self.standings.iter().enumerate() {
+            writeln!(
+                f,
+                "{:<4}{:<22}{:>10}{:>6}{:>6}{:>6}{:>9.1}%",
+                i + 1,
+                s.name,
+                s.score,
+                s.wins,
+                s.draws,
+                s.losses,
+                s.win_rate() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// Every player meets every other player once, matches are won by winning
+// more rounds.
+pub fn round_robin(
+    rules: &RuleSet,
+    players: &mut [Box<dyn Player>],
+    rounds: usize,
+    seed: u64,
+) -> Leaderboard {
+    let mut rng = Rng::new(seed);
+    let mut standings = players
+        .iter()
+        .map(|player| Standing {
+            name: player.name(),
+            score: 0,
+            matches: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        })
+        .collect::<Vec<_>>();
+    for i in 0..players.len() {
+        for j in i + 1..players.len() {
+            let (left, right) = players.split_at_mut(j);
+            let result = play_match(
+                rules,
+                [left[i].as_mut(), right[0].as_mut()],
+                rounds,
+                &mut rng,
+            );
+            for (k, player) in [(0, i), (1, j)] {
+                let standing = &mut standings[player];
+                standing.score += result.scores[k];
+                standing.matches += 1;
+                match result.wins[k].cmp(&result.wins[1 - k]) {
+                    std::cmp::Ordering::Greater => standing.wins += 1,
+                    std::cmp::Ordering::Equal => standing.draws += 1,
+                    std::cmp::Ordering::Less => standing.losses += 1,
+                }
+            }
+        }
+    }
+    standings.sort_by_key(|standing| std::cmp::Reverse(standing.score));
+    Leaderboard { standings }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bots(rules: &RuleSet) -> Vec<Box<dyn Player>> {
+        vec![
+            Box::new(GuideReplay::new(rules, "A Y\nB X\nC Z", Strategy::Moves).unwrap()),
+            Box::new(Random),
+            Box::new(FrequencyCounter::default()),
+            Box::new(PatternMatcher::new(2)),
+            Box::new(WinStayLoseShift::default()),
+        ]
+    }
+
+    #[test]
+    fn test_counter_beats_constant_player() {
+        let rules = RuleSet::classic();
+        let mut rocks = GuideReplay::new(&rules, "A X", Strategy::Moves).unwrap();
+        let mut counter = FrequencyCounter::default();
+        let result = play_match(&rules, [&mut counter, &mut rocks], 100, &mut Rng::new(1));
+        assert!(result.wins[0] >= 99);
+        assert!(result.wins[1] <= 1);
+    }
+
+    #[test]
+    fn test_pattern_matcher_learns_cycle() {
+        let rules = RuleSet::classic();
+        let mut cycle = GuideReplay::new(&rules, "A X\nA Y\nA Z", Strategy::Moves).unwrap();
+        let mut matcher = PatternMatcher::new(2);
+        let result = play_match(&rules, [&mut matcher, &mut cycle], 99, &mut Rng::new(1));
+        assert!(result.wins[0] >= 95);
+    }
+
+    #[test]
+    fn test_win_stay_lose_shift() {
+        let rules = RuleSet::classic();
+        let mut player = WinStayLoseShift::default();
+        let mut rng = Rng::new(1);
+        player.observe(&rules, 1, 0);
+        assert_eq!(player.choose(&rules, &mut rng), 1);
+        player.observe(&rules, 1, 2);
+        assert_eq!(player.choose(&rules, &mut rng), 0);
+    }
+
+    #[test]
+    fn test_round_robin_is_reproducible() {
+        let rules = RuleSet::classic();
+        let first = round_robin(&rules, &mut bots(&rules), 200, 7);
+        let second = round_robin(&rules, &mut bots(&rules), 200, 7);
+        assert_eq!(first.standings, second.standings);
+        assert_eq!(first.standings.len(), 5);
+        assert!(first.standings.iter().all(|s| s.matches == 4));
+        assert!(first.standings.windows(2).all(|w| w[0].score >= w[1].score));
+    }
+}
@@ -0,0 +1,95 @@
+use std::{hint::black_box, time::Instant};
+
+use day3_rucksack_reorganization::{part1, part2};
+
+// The str::contains based solution the bitsets replaced
+fn priority(c: char) -> u32 {
+    match c {
+        'a'..='z' => u32::from(c) - u32::from(b'a') + 1,
+        'A'..='Z' => u32::from(c) - u32::from(b'A') + 27,
+        _ => 0,
+    }
+}
+
+fn contains_part1(data: &str) -> u32 {
+    data.split_whitespace()
+        .flat_map(|rucksack| {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            first.chars().find(|&c| second.contains(c)).map(priority)
+        })
+        .sum()
+}
+
+fn contains_part2(data: &str) -> u32 {
+    let v = data.split_whitespace().collect::<Vec<_>>();
+    v.chunks(3)
+        .flat_map(|group| {
+            let (first, second, third) = (group[0], group[1], group[2]);
+            first
+                .chars()
+                .find(|&c| second.contains(c) && third.contains(c))
+                .map(priority)
+        })
+        .sum()
+}
+
+// Rucksacks of `size` items. Each member of a group of three draws from its
+// own 14 item types, so the compartments only share the planted duplicate
+// and the group only shares the planted badge.
+fn generate(rucksacks: usize, size: usize) -> String {
+    let alphabet = ('a'..='z').chain('A'..='Z').collect::<Vec<_>>();
+    let mut seed = 2022_u64;
+    let mut next = |n: usize| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) as usize % n
+    };
+    (0..rucksacks)
+        .map(|i| {
+            let badge = alphabet[(i / 3) % 4];
+            let duplicate = alphabet[4 + i % 3];
+            let pool = &alphabet[8 + 14 * (i % 3)..][..14];
+            let mut first = (0..size / 2 - 1).map(|_| pool[next(7)]).collect::<String>();
+            let mut second = (0..size / 2 - 2)
+                .map(|_| pool[7 + next(7)])
+                .collect::<String>();
+            first.push(duplicate);
+            second.push(badge);
+            second.push(duplicate);
+            first + &second
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Best of a few runs
+fn bench(name: &str, data: &str, f: fn(&str) -> u32) -> u32 {
+    let (result, elapsed) = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f(black_box(data)));
+            (result, start.elapsed())
+        })
+        .min_by_key(|&(_, elapsed)| elapsed)
+        .unwrap();
+    println!("{name:<24}{elapsed:>12.3?}");
+    result
+}
+
+fn main() {
+    for (rucksacks, size) in [(3_000, 48), (3_000, 1_000), (99, 20_000)] {
+        let data = generate(rucksacks, size);
+        println!("{rucksacks} rucksacks of {size} items");
+        let expected = bench("  part1 str::contains", &data, contains_part1);
+        assert_eq!(
+            bench("  part1 bitset", &data, |data| part1(data).unwrap()),
+            expected
+        );
+        let expected = bench("  part2 str::contains", &data, contains_part2);
+        assert_eq!(
+            bench("  part2 bitset", &data, |data| part2(data).unwrap()),
+            expected
+        );
+    }
+}
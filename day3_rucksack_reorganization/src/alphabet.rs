@@ -0,0 +1,159 @@
+use std::{error::Error, fs, str::FromStr};
+
+// The item types rucksacks may hold, each with its priority. Item type i is
+// bit i of the masks rucksacks are packed into, so there are at most 64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemAlphabet {
+    items: Vec<char>,
+    priorities: Vec<u32>,
+}
+
+impl ItemAlphabet {
+    pub fn new(items: &[(char, u32)]) -> Result<Self, Box<dyn Error>> {
+        if items.len() > 64 {
+            return Err(format!("{} item types, at most 64 are supported", items.len()).into());
+        }
+        let mut alphabet = ItemAlphabet {
+            items: Vec::new(),
+            priorities: Vec::new(),
+        };
+        for &(item, priority) in items {
+            if alphabet.items.contains(&item) {
+                return Err(format!("Duplicated item type {item}").into());
+            }
+            alphabet.items.push(item);
+            alphabet.priorities.push(priority);
+        }
+        Ok(alphabet)
+    }
+
+    // a-z are 1 to 26 and A-Z 27 to 52, as the puzzle says
+    pub fn letters() -> Self {
+        let items = ('a'..='z').chain('A'..='Z').zip(1..).collect::<Vec<_>>();
+        ItemAlphabet::new(&items).expect("Letters are unique")
+    }
+
+    // The letters followed by 0-9 with priorities 53 to 62
+    pub fn letters_and_digits() -> Self {
+        let items = ('a'..='z')
+            .chain('A'..='Z')
+            .chain('0'..='9')
+            .zip(1..)
+            .collect::<Vec<_>>();
+        ItemAlphabet::new(&items).expect("Letters and digits are unique")
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, Box<dyn Error>> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn item(&self, c: char) -> Result<u64, Box<dyn Error>> {
+        self.items
+            .iter()
+            .position(|&item| item == c)
+            .map(|i| 1 << i)
+            .ok_or_else(|| format!("Unknown item {c}").into())
+    }
+
+    pub fn items(&self, s: &str) -> Result<u64, Box<dyn Error>> {
+        s.chars().try_fold(0, |mask, c| Ok(mask | self.item(c)?))
+    }
+
+    // The item types of a mask, in alphabet order
+    pub fn symbols(&self, mask: u64) -> String {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| mask >> i & 1 == 1)
+            .map(|(_, &item)| item)
+            .collect()
+    }
+
+    // Priority of the first item type of the mask, 0 when it is empty
+    pub fn priority(&self, mask: u64) -> u32 {
+        match mask {
+            0 => 0,
+            _ => self.priorities[mask.trailing_zeros() as usize],
+        }
+    }
+}
+
+impl Default for ItemAlphabet {
+    fn default() -> Self {
+        ItemAlphabet::letters()
+    }
+}
+
+// One `<item> <priority>` per line, blank lines and `#` comments are ignored
+impl FromStr for ItemAlphabet {
+    type Err = Box<dyn Error>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let items = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+            .map(|(i, line)| {
+                let error = || format!("line {}: expected `<item> <priority>`", i + 1);
+                let (item, priority) = line
+                    .trim()
+                    .rsplit_once(char::is_whitespace)
+                    .ok_or_else(error)?;
+                let mut item = item.trim().chars();
+                match (item.next(), item.next()) {
+                    (Some(item), None) => Ok((item, priority.parse().map_err(|_| error())?)),
+                    _ => Err(error()),
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        ItemAlphabet::new(&items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alphabets() {
+        let letters = ItemAlphabet::default();
+        assert_eq!(letters.priority(letters.item('p').unwrap()), 16);
+        assert_eq!(letters.priority(letters.item('L').unwrap()), 38);
+        assert!(letters.item('7').is_err());
+
+        let digits = ItemAlphabet::letters_and_digits();
+        assert_eq!(digits.priority(digits.item('7').unwrap()), 60);
+        assert_eq!(digits.symbols(digits.items("b7a").unwrap()), "ab7");
+    }
+
+    #[test]
+    fn test_custom_alphabet() {
+        let alphabet = "# fruit
+🍎 10
+🍌 20
+é 5"
+        .parse::<ItemAlphabet>()
+        .unwrap();
+        assert_eq!(alphabet.len(), 3);
+        assert_eq!(alphabet.priority(alphabet.item('🍌').unwrap()), 20);
+        assert_eq!(alphabet.priority(alphabet.item('é').unwrap()), 5);
+        assert!(alphabet.item('a').is_err());
+
+        assert!("a 1\na 2".parse::<ItemAlphabet>().is_err());
+        assert!("ab 1".parse::<ItemAlphabet>().is_err());
+        assert!("a one".parse::<ItemAlphabet>().is_err());
+        let too_many = (0..65)
+            .map(|i| format!("{} {i}", char::from_u32(0x100 + i).unwrap()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(too_many.parse::<ItemAlphabet>().is_err());
+    }
+}
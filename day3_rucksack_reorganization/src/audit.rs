@@ -0,0 +1,169 @@
+use std::fmt::{self, Display};
+
+use crate::{compartments, ItemAlphabet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SharedItem {
+    pub item: char,
+    pub first: usize,
+    pub second: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Issue {
+    // The compartments cannot have the same size
+    OddLength,
+    UnknownItem(char),
+    NoSharedItem,
+    MultipleSharedItems,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RucksackAudit {
+    pub line: usize,
+    pub contents: String,
+    pub shared: Vec<SharedItem>,
+    pub issues: Vec<Issue>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub rucksacks: Vec<RucksackAudit>,
+}
+
+impl Report {
+    pub fn flagged(&self) -> impl Iterator<Item = &RucksackAudit> {
+        self.rucksacks.iter().filter(|r| !r.issues.is_empty())
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.flagged().next().is_none()
+    }
+}
+
+impl Display for RucksackAudit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.contents)?;
+        for shared in &self.shared {
+            write!(f, " {}({}/{})", shared.item, shared.first, shared.second)?;
+        }
+        if !self.issues.is_empty() {
+            write!(f, " {:?}", self.issues)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for rucksack in self.flagged() {
+            writeln!(f, "{rucksack}")?;
+        }
+        write!(
+            f,
+            "{} rucksacks, {} flagged",
+            self.rucksacks.len(),
+            self.flagged().count()
+        )
+    }
+}
+
+// Every item type found in both compartments, with how many of it each
+// compartment holds, and whatever makes a rucksack not follow the rules.
+pub fn audit(data: &str, alphabet: &ItemAlphabet) -> Report {
+    let rucksacks = data
+        .lines()
+        .enumerate()
+        .map(|(i, contents)| {
+            let contents = contents.trim();
+            let mut issues = Vec::new();
+            if contents.chars().count() % 2 == 1 {
+                issues.push(Issue::OddLength);
+            }
+            for c in contents.chars().filter(|&c| alphabet.item(c).is_err()) {
+                if !issues.contains(&Issue::UnknownItem(c)) {
+                    issues.push(Issue::UnknownItem(c));
+                }
+            }
+
+            let (first, second) = compartments(contents);
+            let mask = |s: &str| {
+                s.chars()
+                    .flat_map(|c| alphabet.item(c))
+                    .fold(0, |mask, item| mask | item)
+            };
+            let count = |s: &str, c: char| s.chars().filter(|&x| x == c).count();
+            let shared = alphabet
+                .symbols(mask(first) & mask(second))
+                .chars()
+                .map(|c| SharedItem {
+                    item: c,
+                    first: count(first, c),
+                    second: count(second, c),
+                })
+                .collect::<Vec<_>>();
+            match shared.len() {
+                0 => issues.push(Issue::NoSharedItem),
+                1 => {}
+                _ => issues.push(Issue::MultipleSharedItems),
+            }
+            RucksackAudit {
+                line: i + 1,
+                contents: contents.to_string(),
+                shared,
+                issues,
+            }
+        })
+        .collect();
+    Report { rucksacks }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit() {
+        let report = audit(
+            "vJrwpWtwJgWrhcsFMMfFFhFp\nabcde\nabcd\naabBab\na1?a",
+            &ItemAlphabet::default(),
+        );
+        assert_eq!(report.rucksacks.len(), 5);
+        assert_eq!(
+            report.rucksacks[0].shared,
+            vec![SharedItem {
+                item: 'p',
+                first: 1,
+                second: 1
+            }]
+        );
+        assert!(report.rucksacks[0].issues.is_empty());
+        assert_eq!(
+            report.rucksacks[1].issues,
+            vec![Issue::OddLength, Issue::NoSharedItem]
+        );
+        assert_eq!(report.rucksacks[2].issues, vec![Issue::NoSharedItem]);
+        assert_eq!(
+            report.rucksacks[3].shared,
+            vec![
+                SharedItem {
+                    item: 'a',
+                    first: 2,
+                    second: 1
+                },
+                SharedItem {
+                    item: 'b',
+                    first: 1,
+                    second: 1
+                }
+            ]
+        );
+        assert_eq!(report.rucksacks[3].issues, vec![Issue::MultipleSharedItems]);
+        assert_eq!(
+            report.rucksacks[4].issues,
+            vec![Issue::UnknownItem('1'), Issue::UnknownItem('?')]
+        );
+        assert_eq!(report.flagged().count(), 4);
+        assert!(!report.is_clean());
+    }
+}
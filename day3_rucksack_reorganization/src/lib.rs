@@ -1,4 +1,11 @@
-use std::fs;
+use std::{error::Error, fs};
+
+pub mod alphabet;
+pub mod audit;
+pub mod regroup;
+pub mod repack;
+
+pub use alphabet::ItemAlphabet;
 
 pub fn data() -> String {
     fs::read_to_string("input.txt").unwrap().trim().to_string()
@@ -6,38 +13,107 @@ pub fn data() -> String {
 
 // --- Day 3: Rucksack Reorganization ---
 
-pub fn part1(data: &str) -> u32 {
-    data.split_whitespace()
-        .flat_map(|rucksack| {
-            let (first, second) = rucksack.split_at(rucksack.len() / 2);
-            first.chars().find(|&c| second.contains(c)).map(|c| -> u32 {
-                match c {
-                    'a'..='z' => u32::from(c) - u32::from(b'a') + 1,
-                    'A'..='Z' => u32::from(c) - u32::from(b'A') + 27,
-                    _ => 0,
-                }
-            })
+struct Rucksack {
+    first: u64,
+    second: u64,
+}
+
+impl Rucksack {
+    fn parse(s: &str, alphabet: &ItemAlphabet) -> Result<Self, Box<dyn Error>> {
+        let (first, second) = compartments(s);
+        Ok(Rucksack {
+            first: alphabet.items(first)?,
+            second: alphabet.items(second)?,
         })
+    }
+
+    fn items(&self) -> u64 {
+        self.first | self.second
+    }
+
+    fn shared(&self) -> u64 {
+        self.first & self.second
+    }
+}
+
+// Split in half by items, the first compartment is the short one when the
+// count is odd.
+fn compartments(s: &str) -> (&str, &str) {
+    let half = s.chars().count() / 2;
+    s.split_at(s.char_indices().nth(half).map_or(s.len(), |(i, _)| i))
+}
+
+fn rucksacks<'a>(
+    data: &'a str,
+    alphabet: &'a ItemAlphabet,
+) -> impl Iterator<Item = Result<Rucksack, Box<dyn Error>>> + 'a {
+    data.lines().enumerate().map(|(i, rucksack)| {
+        Rucksack::parse(rucksack.trim(), alphabet)
+            .map_err(|e| format!("line {}: {e}", i + 1).into())
+    })
+}
+
+// Sum of the priorities of the item type both compartments share
+pub fn priorities(data: &str, alphabet: &ItemAlphabet) -> Result<u32, Box<dyn Error>> {
+    rucksacks(data, alphabet)
+        .map(|rucksack| Ok(alphabet.priority(rucksack?.shared())))
         .sum()
 }
 
-pub fn part2(data: &str) -> u32 {
-    let v = data.split_whitespace().collect::<Vec<_>>();
-    v.chunks(3)
-        .flat_map(|group| {
-            let (first, second, third) = (group[0], group[1], group[2]);
-            first
-                .chars()
-                .find(|&c| second.contains(c) && third.contains(c))
-                .map(|c| match c {
-                    'a'..='z' => u32::from(c) - u32::from(b'a') + 1,
-                    'A'..='Z' => u32::from(c) - u32::from(b'A') + 27,
-                    _ => 0,
-                })
+pub fn part1(data: &str) -> Result<u32, Box<dyn Error>> {
+    priorities(data, &ItemAlphabet::default())
+}
+
+fn line_range(first: usize, last: usize) -> String {
+    match first == last {
+        true => format!("line {first}"),
+        false => format!("lines {first}-{last}"),
+    }
+}
+
+// Sum of the badge priorities of consecutive groups of `group_size` elves.
+// Every group must be complete and share exactly one item type.
+pub fn badges(
+    data: &str,
+    group_size: usize,
+    alphabet: &ItemAlphabet,
+) -> Result<u32, Box<dyn Error>> {
+    if group_size == 0 {
+        return Err("Groups need at least one elf".into());
+    }
+    let v = rucksacks(data, alphabet)
+        .map(|rucksack| Ok(rucksack?.items()))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    v.chunks(group_size)
+        .enumerate()
+        .map(|(i, group)| {
+            let first = i * group_size + 1;
+            let lines = line_range(first, first + group.len() - 1);
+            if group.len() < group_size {
+                return Err(format!(
+                    "{lines}: incomplete group of {} rucksacks, expected {group_size}",
+                    group.len()
+                )
+                .into());
+            }
+            let common = group.iter().fold(u64::MAX, |common, items| common & items);
+            match common.count_ones() {
+                1 => Ok(alphabet.priority(common)),
+                0 => Err(format!("{lines}: no item type common to the group").into()),
+                n => Err(format!(
+                    "{lines}: {n} item types common to the group ({})",
+                    alphabet.symbols(common)
+                )
+                .into()),
+            }
         })
         .sum()
 }
 
+pub fn part2(data: &str) -> Result<u32, Box<dyn Error>> {
+    badges(data, 3, &ItemAlphabet::default())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -51,13 +127,45 @@ CrZsJsPPZsGzwwsLwLmpwMDw";
 
     #[test]
     fn test_part1() {
-        let result = part1(DATA);
+        let result = part1(DATA).unwrap();
         assert_eq!(result, 157);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(DATA);
+        let result = part2(DATA).unwrap();
         assert_eq!(result, 70);
     }
+
+    #[test]
+    fn test_badges() {
+        let letters = ItemAlphabet::default();
+        assert_eq!(badges("ab\nbc\nbd\nbe", 2, &letters).unwrap(), 4);
+
+        let error = badges(
+            &DATA.lines().take(5).collect::<Vec<_>>().join("\n"),
+            3,
+            &letters,
+        );
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "lines 4-5: incomplete group of 2 rucksacks, expected 3"
+        );
+        let error = badges("abcd\nabce", 2, &letters).unwrap_err().to_string();
+        assert_eq!(error, "lines 1-2: 3 item types common to the group (abc)");
+        let error = badges("ab\ncd", 2, &letters).unwrap_err().to_string();
+        assert_eq!(error, "lines 1-2: no item type common to the group");
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let error = part1("ab1ab2").unwrap_err().to_string();
+        assert_eq!(error, "line 1: Unknown item 1");
+        let digits = ItemAlphabet::letters_and_digits();
+        assert_eq!(priorities("ab1cd1", &digits).unwrap(), 54);
+        assert_eq!(
+            priorities("🍎x🍌🍎", &"🍎 3\n🍌 1\nx 7".parse().unwrap()).unwrap(),
+            3
+        );
+    }
 }
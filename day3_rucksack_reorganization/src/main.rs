@@ -1,6 +1,40 @@
+use std::{env, error::Error};
+
 use day3_rucksack_reorganization::*;
 
-fn main() {
-    println!("Part 1: {}", part1(&data()));
-    println!("Part 2: {}", part2(&data()));
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut alphabet = ItemAlphabet::default();
+    let mut mode = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--alphabet" => {
+                alphabet = match args.next().as_deref() {
+                    Some("letters") => ItemAlphabet::letters(),
+                    Some("digits") => ItemAlphabet::letters_and_digits(),
+                    Some(path) => ItemAlphabet::from_file(path)?,
+                    None => return Err("Missing alphabet".into()),
+                }
+            }
+            "--audit" | "--repack" | "--unordered" => mode = Some(arg),
+            _ => return Err(format!("Unknown argument {arg}").into()),
+        }
+    }
+
+    match mode.as_deref() {
+        Some("--audit") => println!("{}", audit::audit(&data(), &alphabet)),
+        Some("--repack") => {
+            let (repacked, swaps) = repack::repack(&data())?;
+            println!("{repacked}");
+            eprintln!("{swaps} swaps");
+        }
+        Some("--unordered") => {
+            println!("Badges: {}", regroup::unordered_badges(&data(), &alphabet)?)
+        }
+        _ => {
+            println!("Part 1: {}", priorities(&data(), &alphabet)?);
+            println!("Part 2: {}", badges(&data(), 3, &alphabet)?);
+        }
+    }
+    Ok(())
 }
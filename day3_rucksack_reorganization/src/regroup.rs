@@ -0,0 +1,179 @@
+use std::error::Error;
+
+use crate::{rucksacks, ItemAlphabet};
+
+// Exact cover over every triple of rucksacks sharing exactly one item type,
+// always covering next the rucksack left with the fewest live triples.
+struct Search {
+    triples: Vec<[usize; 3]>,
+    // Triples each rucksack belongs to
+    member_of: Vec<Vec<usize>>,
+    live: Vec<bool>,
+    // Live triples per rucksack
+    options: Vec<usize>,
+    used: Vec<bool>,
+    groups: Vec<[usize; 3]>,
+}
+
+impl Search {
+    fn new(items: &[u64]) -> Self {
+        let n = items.len();
+        let mut triples = Vec::new();
+        for a in 0..n {
+            for b in a + 1..n {
+                let common = items[a] & items[b];
+                if common == 0 {
+                    continue;
+                }
+                for (c, &third) in items.iter().enumerate().skip(b + 1) {
+                    if (common & third).count_ones() == 1 {
+                        triples.push([a, b, c]);
+                    }
+                }
+            }
+        }
+        let mut member_of = vec![Vec::new(); n];
+        triples
+            .iter()
+            .enumerate()
+            .for_each(|(t, triple)| triple.iter().for_each(|&i| member_of[i].push(t)));
+        Search {
+            live: vec![true; triples.len()],
+            options: member_of.iter().map(Vec::len).collect(),
+            triples,
+            member_of,
+            used: vec![false; n],
+            groups: Vec::new(),
+        }
+    }
+
+    // Take a triple, killing every other triple sharing one of its rucksacks
+    fn select(&mut self, t: usize) -> Vec<usize> {
+        let mut killed = Vec::new();
+        for i in self.triples[t] {
+            self.used[i] = true;
+            for &u in &self.member_of[i] {
+                if self.live[u] {
+                    self.live[u] = false;
+                    self.triples[u].iter().for_each(|&j| self.options[j] -= 1);
+                    killed.push(u);
+                }
+            }
+        }
+        self.groups.push(self.triples[t]);
+        killed
+    }
+
+    fn deselect(&mut self, t: usize, killed: Vec<usize>) {
+        self.groups.pop();
+        for u in killed.into_iter().rev() {
+            self.live[u] = true;
+            self.triples[u].iter().for_each(|&j| self.options[j] += 1);
+        }
+        self.triples[t].iter().for_each(|&i| self.used[i] = false);
+    }
+
+    fn solve(&mut self) -> bool {
+        let Some(next) = (0..self.used.len())
+            .filter(|&i| !self.used[i])
+            .min_by_key(|&i| self.options[i])
+        else {
+            return true;
+        };
+        let candidates = self.member_of[next]
+            .iter()
+            .copied()
+            .filter(|&t| self.live[t])
+            .collect::<Vec<_>>();
+        for t in candidates {
+            let killed = self.select(t);
+            if self.solve() {
+                return true;
+            }
+            self.deselect(t, killed);
+        }
+        false
+    }
+}
+
+// Split rucksacks, given in any order as their item masks, into groups of three elves sharing
+// exactly one item type, or None once every possibility has been ruled out.
+pub fn recover_groups(items: &[u64]) -> Option<Vec<[usize; 3]>> {
+    if !items.len().is_multiple_of(3) {
+        return None;
+    }
+    let mut search = Search::new(items);
+    search.solve().then_some(search.groups)
+}
+
+// Badge priorities of a grouping recovered from unordered rucksacks
+pub fn unordered_badges(data: &str, alphabet: &ItemAlphabet) -> Result<u32, Box<dyn Error>> {
+    let items = rucksacks(data, alphabet)
+        .map(|rucksack| Ok(rucksack?.items()))
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    if !items.len().is_multiple_of(3) {
+        return Err(format!("{} rucksacks cannot form groups of three", items.len()).into());
+    }
+    let groups = recover_groups(&items).ok_or("No grouping shares one badge per group")?;
+    Ok(groups
+        .iter()
+        .map(|group| alphabet.priority(group.iter().fold(u64::MAX, |common, &i| common & items[i])))
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn masks(rucksacks: &[&str]) -> Vec<u64> {
+        let letters = ItemAlphabet::default();
+        rucksacks
+            .iter()
+            .map(|rucksack| letters.items(rucksack).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_recover_groups() {
+        let rucksacks = [
+            "ttgJtRGJQctTZtZT",
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "CrZsJsPPZsGzwwsLwLmpwMDw",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn",
+            "PmmdzqPrVvPwwTWBwg",
+        ];
+        let items = masks(&rucksacks);
+        let groups = recover_groups(&items).unwrap();
+        assert_eq!(groups.len(), 2);
+        let mut seen = groups.concat();
+        seen.sort_unstable();
+        assert_eq!(seen, vec![0, 1, 2, 3, 4, 5]);
+        let badges = groups
+            .iter()
+            .map(|group| items[group[0]] & items[group[1]] & items[group[2]])
+            .collect::<Vec<_>>();
+        assert!(badges.iter().all(|badge| badge.count_ones() == 1));
+        let letters = ItemAlphabet::default();
+        assert_eq!(
+            unordered_badges(&rucksacks.join("\n"), &letters).unwrap(),
+            badges
+                .into_iter()
+                .map(|badge| letters.priority(badge))
+                .sum()
+        );
+    }
+
+    #[test]
+    fn test_no_grouping() {
+        let letters = ItemAlphabet::default();
+        assert_eq!(
+            recover_groups(&masks(&["ab", "ac", "ad", "xy", "xz", "yz"])),
+            None
+        );
+        assert_eq!(recover_groups(&masks(&["ab", "ab"])), None);
+        assert!(unordered_badges("ab\nab", &letters).is_err());
+        assert!(unordered_badges("ab\nac\nad\nxy\nxz\nyz", &letters).is_err());
+        assert!(unordered_badges("ab\nac\na1", &letters).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use std::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    // Positions swapped, one in each compartment
+    pub swaps: Vec<(usize, usize)>,
+    pub repacked: String,
+}
+
+// Choose the item types that end up in the first compartment: they must fill
+// it exactly, and every item of theirs sitting in the second compartment costs
+// one swap. A knapsack over the item types finds the cheapest choice.
+pub fn plan(rucksack: &str) -> Result<Plan, Box<dyn Error>> {
+    let mut items = rucksack.chars().collect::<Vec<_>>();
+    if items.len() % 2 == 1 {
+        return Err(format!("{} items cannot fill two equal compartments", items.len()).into());
+    }
+    let half = items.len() / 2;
+
+    let mut types = items.clone();
+    types.sort_unstable();
+    types.dedup();
+    let counts = types
+        .iter()
+        .map(|&t| {
+            let count = |items: &[char]| items.iter().filter(|&&c| c == t).count();
+            (count(&items[..half]), count(&items[half..]))
+        })
+        .collect::<Vec<_>>();
+    if let Some((t, (first, second))) = types
+        .iter()
+        .zip(&counts)
+        .find(|(_, (first, second))| first + second > half)
+    {
+        return Err(format!(
+            "{t} occurs {} times, more than a compartment holds",
+            first + second
+        )
+        .into());
+    }
+
+    // cost[i][size]: fewest swaps filling `size` slots with the first i types
+    let mut cost = vec![vec![None; half + 1]; types.len() + 1];
+    cost[0][0] = Some(0);
+    for (i, &(first, second)) in counts.iter().enumerate() {
+        for size in 0..=half {
+            let skip = cost[i][size];
+            let take = (size >= first + second)
+                .then(|| cost[i][size - first - second].map(|c| c + second))
+                .flatten();
+            cost[i + 1][size] = match (skip, take) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+    if cost[types.len()][half].is_none() {
+        return Err("Item types cannot be split into equal compartments".into());
+    }
+    let mut in_first = vec![false; types.len()];
+    let mut size = half;
+    for i in (0..types.len()).rev() {
+        if cost[i + 1][size] != cost[i][size] {
+            let (first, second) = counts[i];
+            in_first[i] = true;
+            size -= first + second;
+        }
+    }
+
+    let belongs_first = |c: char| in_first[types.binary_search(&c).unwrap()];
+    let leaving = (0..half).filter(|&i| !belongs_first(items[i]));
+    let arriving = (half..items.len()).filter(|&i| belongs_first(items[i]));
+    let swaps = leaving.zip(arriving).collect::<Vec<_>>();
+    swaps.iter().for_each(|&(i, j)| items.swap(i, j));
+    Ok(Plan {
+        swaps,
+        repacked: items.into_iter().collect(),
+    })
+}
+
+// The input with every rucksack repacked, and the total number of swaps
+pub fn repack(data: &str) -> Result<(String, usize), Box<dyn Error>> {
+    let plans = data
+        .lines()
+        .enumerate()
+        .map(|(i, rucksack)| plan(rucksack.trim()).map_err(|e| format!("line {}: {e}", i + 1)))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((
+        plans
+            .iter()
+            .map(|plan| plan.repacked.as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        plans.iter().map(|plan| plan.swaps.len()).sum(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ItemAlphabet, Rucksack};
+
+    #[test]
+    fn test_plan() {
+        let plan = plan("abab").unwrap();
+        assert_eq!(plan.swaps.len(), 1);
+        assert_eq!(plan.repacked, "aabb");
+
+        // Swapping one b for a c beats bringing the other three c over
+        let plan = super::plan("acabcccb").unwrap();
+        assert_eq!(plan.swaps.len(), 1);
+        assert_eq!(
+            Rucksack::parse(&plan.repacked, &ItemAlphabet::default())
+                .unwrap()
+                .shared(),
+            0
+        );
+
+        assert!(super::plan("aaab").is_err());
+        assert!(super::plan("abc").is_err());
+        assert!(super::plan("aabbbbcc").is_ok());
+        assert!(super::plan("aaabbbcc").is_err());
+    }
+
+    #[test]
+    fn test_repack() {
+        let data = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg";
+        let (repacked, _) = repack(data).unwrap();
+        assert_eq!(repacked.lines().count(), 3);
+        assert!(repacked.lines().all(|rucksack| Rucksack::parse(
+            rucksack,
+            &ItemAlphabet::default()
+        )
+        .unwrap()
+        .shared()
+            == 0));
+        assert_eq!(repack("abcd\nab").unwrap().1, 0);
+        assert!(repack("abcd\naaab")
+            .unwrap_err()
+            .to_string()
+            .starts_with("line 2"));
+    }
+}
@@ -0,0 +1,77 @@
+use std::{hint::black_box, ops::RangeInclusive, time::Instant};
+
+use day4_camp_cleanup::{part1, part2};
+
+// The per-section iteration the endpoint comparisons replaced
+fn ranges(pair: &str) -> (RangeInclusive<u64>, RangeInclusive<u64>) {
+    let range = |s: &str| {
+        let (start, end) = s.split_once('-').unwrap();
+        start.parse().unwrap()..=end.parse().unwrap()
+    };
+    let (first, second) = pair.split_once(',').unwrap();
+    (range(first), range(second))
+}
+
+fn iterating_part1(data: &str) -> u32 {
+    data.lines()
+        .map(ranges)
+        .filter(|(a, b)| a.clone().all(|x| b.contains(&x)) || b.clone().all(|x| a.contains(&x)))
+        .count() as u32
+}
+
+fn iterating_part2(data: &str) -> u32 {
+    data.lines()
+        .map(ranges)
+        .filter(|(a, b)| a.clone().any(|x| b.contains(&x)) || b.clone().any(|x| a.contains(&x)))
+        .count() as u32
+}
+
+// Pairs of assignments about `width` sections wide
+fn generate(pairs: usize, width: u64) -> String {
+    let mut seed = 2022_u64;
+    let mut next = |n: u64| {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % n
+    };
+    (0..pairs)
+        .map(|_| {
+            let (a, b) = (1 + next(width), 1 + next(width));
+            let (c, d) = (a + next(width), b + next(width));
+            format!("{a}-{c},{b}-{d}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Best of a few runs
+fn bench(name: &str, data: &str, f: &dyn Fn(&str) -> u32) -> u32 {
+    let (result, elapsed) = (0..5)
+        .map(|_| {
+            let start = Instant::now();
+            let result = black_box(f(black_box(data)));
+            (result, start.elapsed())
+        })
+        .min_by_key(|&(_, elapsed)| elapsed)
+        .unwrap();
+    println!("{name:<24}{elapsed:>12.3?}");
+    result
+}
+
+fn main() {
+    for width in [100, 10_000, 1_000_000] {
+        let data = generate(1_000, width);
+        println!("1000 pairs about {width} sections wide");
+        let expected = bench("  part1 iterating", &data, &iterating_part1);
+        assert_eq!(
+            bench("  part1 endpoints", &data, &|data| part1(data).unwrap()),
+            expected
+        );
+        let expected = bench("  part2 iterating", &data, &iterating_part2);
+        assert_eq!(
+            bench("  part2 endpoints", &data, &|data| part2(data).unwrap()),
+            expected
+        );
+    }
+}
@@ -1,4 +1,11 @@
-use std::{fs, num::ParseIntError, ops::RangeInclusive, str::FromStr};
+use std::{error::Error, fs, ops::RangeInclusive, str::FromStr};
+
+pub mod optimise;
+pub mod render;
+pub mod sections;
+pub mod sweep;
+
+pub use sections::SectionSet;
 
 pub fn data() -> String {
     fs::read_to_string("input.txt").unwrap().trim().to_string()
@@ -7,77 +14,140 @@ pub fn data() -> String {
 // Advent of Code 2022
 // --- Day 4: Camp Cleanup ---
 struct Section {
-    id: u32,
+    id: u64,
 }
 
 impl FromStr for Section {
-    type Err = ParseIntError;
+    type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Section { id: s.parse()? })
     }
 }
 
-struct Assignment {
+// The sections of one elf, parsed from `start-end`
+pub struct Assignment {
     start: Section,
     end: Section,
 }
 
 impl Assignment {
-    fn range(&self) -> RangeInclusive<u32> {
+    pub fn start(&self) -> u64 {
+        self.start.id
+    }
+
+    pub fn end(&self) -> u64 {
+        self.end.id
+    }
+
+    pub fn range(&self) -> RangeInclusive<u64> {
         self.start.id..=self.end.id
     }
 
+    fn contains(&self, other: &Assignment) -> bool {
+        self.start.id <= other.start.id && other.end.id <= self.end.id
+    }
+
     fn one_contains_the_other(&self, other: &Assignment) -> bool {
-        self.range().into_iter().all(|x| other.range().contains(&x))
-            || other.range().into_iter().all(|x| self.range().contains(&x))
+        self.contains(other) || other.contains(self)
     }
     fn one_overlaps_the_other(&self, other: &Assignment) -> bool {
-        self.range().into_iter().any(|x| other.range().contains(&x))
-            || other.range().into_iter().any(|x| self.range().contains(&x))
+        self.start.id <= other.end.id && other.start.id <= self.end.id
     }
 }
 
 impl FromStr for Assignment {
-    type Err = ParseIntError;
+    type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once('-').unwrap();
-        Ok(Assignment {
+        let (start, end) = s.split_once('-').ok_or("Error parsing Assignment")?;
+        let assignment = Assignment {
             start: start.parse()?,
             end: end.parse()?,
-        })
+        };
+        if assignment.range().is_empty() {
+            return Err(format!("Reversed assignment {s}").into());
+        }
+        Ok(assignment)
     }
 }
 
-struct Pair {
-    first: Assignment,
-    second: Assignment,
+// The assignments of one line, any number of elves
+struct Group {
+    assignments: Vec<Assignment>,
+}
+
+impl Group {
+    fn others(&self, i: usize) -> impl Iterator<Item = &Assignment> {
+        self.assignments
+            .iter()
+            .enumerate()
+            .filter(move |&(j, _)| j != i)
+            .map(|(_, a)| a)
+    }
+
+    fn any_contains_another(&self) -> bool {
+        (0..self.assignments.len()).any(|i| {
+            self.others(i)
+                .any(|other| self.assignments[i].contains(other))
+        })
+    }
+
+    fn any_two_overlap(&self) -> bool {
+        (0..self.assignments.len()).any(|i| {
+            self.others(i)
+                .any(|other| self.assignments[i].one_overlaps_the_other(other))
+        })
+    }
+
+    // Elves whose every section is also assigned to someone else in the group
+    fn redundant(&self) -> usize {
+        (0..self.assignments.len())
+            .filter(|&i| {
+                SectionSet::from_assignments(self.others(i))
+                    .is_superset(&SectionSet::from_assignments([&self.assignments[i]]))
+            })
+            .count()
+    }
 }
 
-impl FromStr for Pair {
-    type Err = ParseIntError;
+impl FromStr for Group {
+    type Err = Box<dyn Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (a1, a2) = s.split_once(',').unwrap();
-        Ok(Pair {
-            first: a1.parse()?,
-            second: a2.parse()?,
+        Ok(Group {
+            assignments: s.split(',').map(str::parse).collect::<Result<_, _>>()?,
         })
     }
 }
 
-pub fn part1(data: &str) -> u32 {
+fn groups(data: &str) -> Result<Vec<Group>, Box<dyn Error>> {
     data.lines()
-        .flat_map(|pair| pair.parse::<Pair>())
-        .filter(|pair| pair.first.one_contains_the_other(&pair.second))
-        .count() as u32
+        .enumerate()
+        .map(|(i, group)| {
+            group
+                .parse()
+                .map_err(|e| format!("line {}: {e}", i + 1).into())
+        })
+        .collect()
 }
-pub fn part2(data: &str) -> u32 {
-    data.lines()
-        .flat_map(|pair| pair.parse::<Pair>())
-        .filter(|pair| pair.first.one_overlaps_the_other(&pair.second))
-        .count() as u32
+
+pub fn part1(data: &str) -> Result<u32, Box<dyn Error>> {
+    Ok(groups(data)?
+        .iter()
+        .filter(|group| group.any_contains_another())
+        .count() as u32)
+}
+pub fn part2(data: &str) -> Result<u32, Box<dyn Error>> {
+    Ok(groups(data)?
+        .iter()
+        .filter(|group| group.any_two_overlap())
+        .count() as u32)
+}
+
+// Elves that could stay home since the rest of their group covers them
+pub fn redundant_elves(data: &str) -> Result<u32, Box<dyn Error>> {
+    Ok(groups(data)?.iter().map(Group::redundant).sum::<usize>() as u32)
 }
 
 #[cfg(test)]
@@ -93,13 +163,41 @@ mod tests {
 
     #[test]
     fn test_part1() {
-        let result = part1(DATA);
+        let result = part1(DATA).unwrap();
         assert_eq!(result, 2);
     }
 
     #[test]
     fn test_part2() {
-        let result = part2(DATA);
+        let result = part2(DATA).unwrap();
         assert_eq!(result, 4);
     }
+
+    #[test]
+    fn test_wide_and_reversed_ranges() {
+        let result = part1("1-4000000000,2-3999999999\n1-2,3-18446744073709551615");
+        assert_eq!(result.unwrap(), 1);
+        let result = part2("1-4000000000,4000000000-4000000001\n1-2,3-4");
+        assert_eq!(result.unwrap(), 1);
+        let error = part1("2-4,6-8\n7-3,1-2").unwrap_err().to_string();
+        assert_eq!(error, "line 2: Reversed assignment 7-3");
+    }
+
+    #[test]
+    fn test_groups() {
+        const GROUPS: &str = "1-3,5-6,3-4
+2-9
+1-5,2-3,5-5,4-4
+1-4,3-6,5-9,7-8";
+        assert_eq!(part1(GROUPS).unwrap(), 2);
+        assert_eq!(part2(GROUPS).unwrap(), 3);
+        // 2-3, 5-5 and 4-4 within 1-5, 7-8 within 5-9, and 3-6 is covered
+        // by 1-4 and 5-9
+        assert_eq!(redundant_elves(GROUPS).unwrap(), 5);
+        assert_eq!(redundant_elves(DATA).unwrap(), 2);
+        assert_eq!(redundant_elves("3-3,3-3").unwrap(), 2);
+
+        let error = part1("1-2,3-4,").unwrap_err().to_string();
+        assert_eq!(error, "line 1: Error parsing Assignment");
+    }
 }
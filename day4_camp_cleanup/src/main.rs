@@ -1,6 +1,49 @@
+use std::{env, error::Error};
+
 use day4_camp_cleanup::*;
 
-fn main() {
-    println!("Part 1: {}", part1(&data()));
-    println!("Part 2: {}", part2(&data()));
+fn main() -> Result<(), Box<dyn Error>> {
+    if let Some(render) = env::args().find(|arg| arg.starts_with("--render")) {
+        match render.as_str() {
+            "--render" | "--render=ascii" => println!("{}", render::render_ascii(&data(), 80)?),
+            "--render=svg" => println!("{}", render::render_svg(&data())?),
+            _ => return Err(format!("Unknown render mode {render}").into()),
+        }
+        return Ok(());
+    }
+    if let Some(i) = env::args().position(|arg| arg == "--optimise") {
+        let bounds = env::args().nth(i + 1).ok_or("Missing section bounds")?;
+        let (low, high) = bounds
+            .split_once('-')
+            .ok_or("Expected bounds as low-high")?;
+        let result = optimise::optimise(&data(), low.parse()?..=high.parse()?)?;
+        println!("{}", result.data);
+        match result.exact {
+            true => eprintln!("Total displacement: {}", result.displacement),
+            false => eprintln!(
+                "Total displacement: {} (best found, may not be minimal)",
+                result.displacement
+            ),
+        }
+        return Ok(());
+    }
+    if env::args().any(|arg| arg == "--sweep") {
+        let result = sweep::sweep(&data())?;
+        println!(
+            "{} overlapping pairs of elves, {} fully contained",
+            result.overlaps.len(),
+            result.overlaps.iter().filter(|o| o.contained).count()
+        );
+        if let Some(section) = result.busiest_section {
+            println!("{} elves on section {section}", result.max_coverage);
+        }
+        for gap in result.gaps {
+            println!("Sections {}-{} uncovered", gap.start(), gap.end());
+        }
+        return Ok(());
+    }
+    println!("Part 1: {}", part1(&data())?);
+    println!("Part 2: {}", part2(&data())?);
+    println!("Redundant elves: {}", redundant_elves(&data())?);
+    Ok(())
 }
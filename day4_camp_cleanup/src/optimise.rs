@@ -0,0 +1,358 @@
+use std::{collections::BinaryHeap, error::Error, ops::RangeInclusive};
+
+use crate::groups;
+
+// Up to this many elves every left to right order is tried
+pub const EXACT_LIMIT: usize = 8;
+// How many places the local search moves an elf beyond that
+const SEARCH_REACH: usize = 3;
+const SEARCH_MARGIN: usize = 8;
+// Starting orders sort by 2 * start + weight * length, so with weight 1 by
+// the midpoint and with more putting short elves first
+const ORDER_WEIGHTS: [i128; 6] = [1, 2, 4, 8, 16, 32];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rearrangement {
+    // The assignment file with the moved assignments
+    pub data: String,
+    // Sum over all elves of how far their first section moved
+    pub displacement: u64,
+    // Whether no other rearrangement moves the elves less in total, only
+    // known for up to EXACT_LIMIT elves
+    pub exact: bool,
+}
+
+struct Elf {
+    start: i128,
+    length: i128,
+}
+
+// Writing the start of each elf as y + (lengths of the elves before it), the
+// y must be non-decreasing. For a fixed order the y are then the targets, the
+// original starts less those lengths, fitted by an L1 isotonic regression,
+// solved with a max-heap (slope trick), then clamped to `low..=high`.
+fn isotonic(targets: &[i128], low: i128, high: i128) -> Vec<i128> {
+    let mut heap = BinaryHeap::new();
+    let mut y = Vec::with_capacity(targets.len());
+    for &target in targets {
+        heap.push(target);
+        if heap.peek().is_some_and(|&top| top > target) {
+            heap.pop();
+            heap.push(target);
+        }
+        y.push(*heap.peek().unwrap());
+    }
+    for i in (0..y.len().saturating_sub(1)).rev() {
+        y[i] = y[i].min(y[i + 1]);
+    }
+    y.into_iter().map(|y| y.clamp(low, high)).collect()
+}
+
+fn targets(elves: &[Elf], order: &[usize], mut before: i128) -> Vec<i128> {
+    order
+        .iter()
+        .map(|&i| {
+            let target = elves[i].start - before;
+            before += elves[i].length;
+            target
+        })
+        .collect()
+}
+
+fn cost(y: &[i128], targets: &[i128]) -> i128 {
+    y.iter().zip(targets).map(|(y, t)| (y - t).abs()).sum()
+}
+
+// Place elves in the given left to right order as close as possible to where
+// they were.
+fn place(elves: &[Elf], order: &[usize], low: i128, high: i128) -> (Vec<i128>, i128) {
+    let needed = elves.iter().map(|elf| elf.length).sum::<i128>();
+    let y = isotonic(&targets(elves, order, 0), low, high - needed + 1);
+
+    let mut starts = vec![0; elves.len()];
+    let mut offset = 0;
+    let mut cost = 0;
+    for (n, &i) in order.iter().enumerate() {
+        starts[i] = y[n] + offset;
+        cost += (starts[i] - elves[i].start).abs();
+        offset += elves[i].length;
+    }
+    (starts, cost)
+}
+
+// Starting from the best of the ORDER_WEIGHTS orders, move an elf up to
+// SEARCH_REACH places or swap two that close while that lowers the cost. A
+// change only shifts the targets between the two places, so it is judged by
+// fitting those alone between their fixed neighbours, which bounds the cost
+// of the best placement. This only finds a local optimum: the best order is
+// an NP-hard scheduling problem.
+fn local_search(elves: &[Elf], low: i128, high: i128) -> (Vec<i128>, i128) {
+    let needed = elves.iter().map(|elf| elf.length).sum::<i128>();
+    let high = high - needed + 1;
+    let mut order = (0..elves.len()).collect::<Vec<_>>();
+    order = ORDER_WEIGHTS
+        .iter()
+        .map(|&weight| {
+            order.sort_by_key(|&i| 2 * elves[i].start + weight * elves[i].length);
+            (
+                place(elves, &order, low, high + needed - 1).1,
+                order.clone(),
+            )
+        })
+        .min()
+        .unwrap()
+        .1;
+    for pass in 0.. {
+        let mut targets = targets(elves, &order, 0);
+        let mut y = isotonic(&targets, low, high);
+        let mut improved = false;
+        // Alternate directions so elves travel either way in one pass
+        let mut places = (0..order.len()).collect::<Vec<_>>();
+        if pass % 2 == 1 {
+            places.reverse();
+        }
+        for i in places {
+            for j in i + 1..(i + SEARCH_REACH + 1).min(order.len()) {
+                // Neighbours within SEARCH_MARGIN may move too
+                let (first, last) = (
+                    i.saturating_sub(SEARCH_MARGIN),
+                    (j + SEARCH_MARGIN).min(order.len() - 1),
+                );
+                let before = elves[order[first]].start - targets[first];
+                let floor = if first > 0 { y[first - 1] } else { low };
+                let ceiling = y.get(last + 1).copied().unwrap_or(high);
+                for change in [
+                    <[usize]>::rotate_left as fn(&mut [usize], usize),
+                    <[usize]>::rotate_right,
+                    |window, _| window.swap(0, window.len() - 1),
+                ] {
+                    let mut window = order[first..=last].to_vec();
+                    change(&mut window[i - first..=j - first], 1);
+                    let window_targets = self::targets(elves, &window, before);
+                    let window_y = isotonic(&window_targets, floor, ceiling);
+                    let current = cost(&y[first..=last], &targets[first..=last]);
+                    if cost(&window_y, &window_targets) < current {
+                        order[first..=last].copy_from_slice(&window);
+                        targets[first..=last].copy_from_slice(&window_targets);
+                        y[first..=last].copy_from_slice(&window_y);
+                        improved = true;
+                    }
+                }
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    place(elves, &order, low, high + needed - 1)
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![Vec::new()];
+    }
+    permutations(n - 1)
+        .into_iter()
+        .flat_map(|p| {
+            (0..n).map(move |i| {
+                let mut p = p.clone();
+                p.insert(i, n - 1);
+                p
+            })
+        })
+        .collect()
+}
+
+// Move assignments, keeping their lengths, so no two elves share a section
+// and every section stays within `bounds`, moving them as little as possible
+// in total. Beyond EXACT_LIMIT elves that is the best the local search
+// finds, which may not be the least possible.
+pub fn optimise(data: &str, bounds: RangeInclusive<u64>) -> Result<Rearrangement, Box<dyn Error>> {
+    let groups = groups(data)?;
+    let elves = groups
+        .iter()
+        .flat_map(|group| &group.assignments)
+        .map(|a| Elf {
+            start: i128::from(a.start.id),
+            length: i128::from(a.end.id - a.start.id) + 1,
+        })
+        .collect::<Vec<_>>();
+    let (low, high) = (i128::from(*bounds.start()), i128::from(*bounds.end()));
+    let available = (high - low + 1).max(0);
+    let needed = elves.iter().map(|elf| elf.length).sum::<i128>();
+    if needed > available {
+        return Err(format!(
+            "The assignments need {needed} sections but only {available} are available"
+        )
+        .into());
+    }
+
+    let (starts, cost) = if elves.len() <= EXACT_LIMIT {
+        permutations(elves.len())
+            .iter()
+            .map(|order| place(&elves, order, low, high))
+            .min_by_key(|&(_, cost)| cost)
+            .unwrap()
+    } else {
+        local_search(&elves, low, high)
+    };
+
+    let mut moved = starts
+        .iter()
+        .zip(&elves)
+        .map(|(&start, elf)| format!("{}-{}", start, start + elf.length - 1));
+    let data = groups
+        .iter()
+        .map(|group| {
+            group
+                .assignments
+                .iter()
+                .map(|_| moved.next().unwrap())
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(Rearrangement {
+        data,
+        displacement: cost as u64,
+        exact: elves.len() <= EXACT_LIMIT,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(data: &str) -> Vec<(u64, u64)> {
+        groups(data)
+            .unwrap()
+            .iter()
+            .flat_map(|group| &group.assignments)
+            .map(|a| (a.start.id, a.end.id))
+            .collect()
+    }
+
+    fn disjoint(data: &str) -> bool {
+        let mut ranges = ranges(data);
+        ranges.sort();
+        ranges.windows(2).all(|w| w[0].1 < w[1].0)
+    }
+
+    #[test]
+    fn test_optimise() {
+        let result = optimise("2-4,6-8", 1..=9).unwrap();
+        assert_eq!(result.data, "2-4,6-8");
+        assert_eq!(result.displacement, 0);
+
+        let result = optimise("2-4,3-5", 1..=9).unwrap();
+        assert_eq!(result.displacement, 2);
+        assert!(disjoint(&result.data));
+
+        let result = optimise("1-3,2-4\n8-9,9-9", 1..=9).unwrap();
+        assert_eq!(result.data, "1-3,4-6\n7-8,9-9");
+        assert_eq!(result.displacement, 3);
+
+        assert!(result.exact);
+
+        assert!(optimise("1-5,3-9", 1..=9).is_err());
+        assert!(optimise("1-2", RangeInclusive::new(3, 2)).is_err());
+    }
+
+    #[test]
+    fn test_brute_force() {
+        let mut seed = 2022_u64;
+        let mut next = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        for _ in 0..50 {
+            let data = (0..2)
+                .map(|_| {
+                    let (a, b) = (1 + next(8), 1 + next(8));
+                    format!("{a}-{},{b}-{}", a + next(3), b + next(3))
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let original = ranges(&data);
+            let lengths = original.iter().map(|(s, e)| e - s + 1).collect::<Vec<_>>();
+
+            // Every placement of the four elves within sections 1-12
+            let mut best = None;
+            for code in 0..12_u64.pow(4) {
+                let starts = (0..4)
+                    .map(|i| 1 + code / 12_u64.pow(i) % 12)
+                    .collect::<Vec<_>>();
+                let mut placed = starts
+                    .iter()
+                    .zip(&lengths)
+                    .map(|(&s, l)| (s, s + l - 1))
+                    .collect::<Vec<_>>();
+                if placed.iter().any(|&(_, e)| e > 12) {
+                    continue;
+                }
+                let cost = starts
+                    .iter()
+                    .zip(&original)
+                    .map(|(&s, &(o, _))| s.abs_diff(o))
+                    .sum::<u64>();
+                placed.sort();
+                if placed.windows(2).all(|w| w[0].1 < w[1].0) {
+                    best = Some(best.map_or(cost, |b: u64| b.min(cost)));
+                }
+            }
+
+            let result = optimise(&data, 1..=12);
+            match best {
+                Some(best) => {
+                    let result = result.unwrap();
+                    assert_eq!(result.displacement, best, "{data}");
+                    assert!(disjoint(&result.data));
+                }
+                None => assert!(result.is_err()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_local_search() {
+        let elves = [(14, 1), (5, 2), (14, 1), (13, 6), (11, 6), (16, 4)]
+            .map(|(start, length)| Elf { start, length });
+        assert_eq!(local_search(&elves, 1, 200).1, 11);
+
+        // Twelve elves piled on sections 1-20, too many to try every order
+        let data = (0..6)
+            .map(|i| format!("{}-{},{}-{}", 1 + i, 3 + 2 * i, 10 - i, 10 + i % 3))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = optimise(&data, 1..=100).unwrap();
+        assert!(!result.exact);
+        assert!(disjoint(&result.data));
+        let (before, after) = (ranges(&data), ranges(&result.data));
+        assert!(after.iter().all(|&(start, end)| 1 <= start && end <= 100));
+        assert!(before
+            .iter()
+            .zip(&after)
+            .all(|(a, b)| a.1 - a.0 == b.1 - b.0));
+        let moved = before
+            .iter()
+            .zip(&after)
+            .map(|(a, b)| a.0.abs_diff(b.0))
+            .sum::<u64>();
+        assert_eq!(result.displacement, moved);
+
+        // No worse than keeping the elves in the order of their midpoints
+        let elves = before
+            .iter()
+            .map(|&(start, end)| Elf {
+                start: start.into(),
+                length: (end - start + 1).into(),
+            })
+            .collect::<Vec<_>>();
+        let mut order = (0..elves.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| 2 * elves[i].start + elves[i].length);
+        assert!(moved as i128 <= place(&elves, &order, 1, 100).1);
+    }
+}
@@ -0,0 +1,252 @@
+use std::{error::Error, fmt::Write};
+
+use crate::{groups, Assignment, Group};
+
+// Assignments within sections 1-9 are drawn digit by digit like the puzzle
+const PUZZLE_SECTIONS: u64 = 9;
+
+// What the assignments of a line share: the sections assigned more than once
+// and whether one assignment lies fully inside another.
+fn shared(assignments: &[Assignment]) -> (Vec<(u64, u64)>, bool) {
+    let mut shared = Vec::new();
+    let mut contained = false;
+    for (i, a) in assignments.iter().enumerate() {
+        for b in &assignments[i + 1..] {
+            contained |= a.one_contains_the_other(b);
+            if a.one_overlaps_the_other(b) {
+                shared.push((a.start.id.max(b.start.id), a.end.id.min(b.end.id)));
+            }
+        }
+    }
+    (shared, contained)
+}
+
+fn label(shared: &[(u64, u64)], contained: bool) -> &'static str {
+    match (shared.is_empty(), contained) {
+        (true, _) => "",
+        (false, true) => "  contained",
+        (false, false) => "  overlap",
+    }
+}
+
+fn bounds(groups: &[Group]) -> (u64, u64) {
+    let assignments = groups.iter().flat_map(|group| &group.assignments);
+    let low = assignments.clone().map(|a| a.start.id).min().unwrap_or(1);
+    let high = assignments.map(|a| a.end.id).max().unwrap_or(1);
+    (low, high)
+}
+
+// One row per assignment, `.234.....  2-4`, then a row of `^` under the
+// sections the assignments share.
+fn puzzle_rows(out: &mut String, assignments: &[Assignment]) {
+    for a in assignments {
+        let row = (1..=PUZZLE_SECTIONS)
+            .map(|id| match a.range().contains(&id) {
+                true => char::from_digit(id as u32, 10).unwrap(),
+                false => '.',
+            })
+            .collect::<String>();
+        writeln!(out, "{row}  {}-{}", a.start.id, a.end.id).unwrap();
+    }
+    let (shared, contained) = shared(assignments);
+    if !shared.is_empty() {
+        let row = (1..=PUZZLE_SECTIONS)
+            .map(|id| match shared.iter().any(|&(s, e)| s <= id && id <= e) {
+                true => '^',
+                false => ' ',
+            })
+            .collect::<String>();
+        writeln!(out, "{row}{}", label(&shared, contained)).unwrap();
+    }
+}
+
+// The same rows squeezed into columns each standing for `scale` sections,
+// counted in u128 since all of u64 is a valid span
+fn scaled_rows(out: &mut String, assignments: &[Assignment], low: u64, columns: u64, scale: u128) {
+    let touches = |s: u64, e: u64, column: u64| {
+        let first = u128::from(low) + u128::from(column) * scale;
+        u128::from(s) < first + scale && first <= u128::from(e)
+    };
+    for a in assignments {
+        let row = (0..columns)
+            .map(|column| match touches(a.start.id, a.end.id, column) {
+                true => '#',
+                false => '.',
+            })
+            .collect::<String>();
+        writeln!(out, "|{row}|  {}-{}", a.start.id, a.end.id).unwrap();
+    }
+    let (shared, contained) = shared(assignments);
+    if !shared.is_empty() {
+        let row = (0..columns)
+            .map(
+                |column| match shared.iter().any(|&(s, e)| touches(s, e, column)) {
+                    true => '^',
+                    false => ' ',
+                },
+            )
+            .collect::<String>();
+        writeln!(out, " {row}{}", label(&shared, contained)).unwrap();
+    }
+}
+
+// Text drawing of every group, in the puzzle's style when the sections fit in
+// 1-9 and otherwise scaled down to at most `width` columns.
+pub fn render_ascii(data: &str, width: usize) -> Result<String, Box<dyn Error>> {
+    let groups = groups(data)?;
+    let (low, high) = bounds(&groups);
+    let mut out = String::new();
+    if low >= 1 && high <= PUZZLE_SECTIONS {
+        for group in &groups {
+            puzzle_rows(&mut out, &group.assignments);
+            out.push('\n');
+        }
+    } else {
+        let span = u128::from(high - low) + 1;
+        let columns = span.min(width.max(1) as u128);
+        let scale = span.div_ceil(columns);
+        let columns = span.div_ceil(scale) as u64;
+        writeln!(out, "sections {low}-{high}, {scale} per column\n")?;
+        for group in &groups {
+            scaled_rows(&mut out, &group.assignments, low, columns, scale);
+            out.push('\n');
+        }
+    }
+    Ok(out.trim_end().to_string())
+}
+
+// SVG timeline: a bar per assignment, shared sections drawn over them in
+// orange, or red when one assignment contains another.
+pub fn render_svg(data: &str) -> Result<String, Box<dyn Error>> {
+    const WIDTH: f64 = 800.0;
+    const BAR: f64 = 10.0;
+    const GAP: f64 = 8.0;
+    const LABEL: f64 = 120.0;
+
+    let groups = groups(data)?;
+    let (low, high) = bounds(&groups);
+    let span = (u128::from(high - low) + 1) as f64;
+    let x = |id: u64| LABEL + (id - low) as f64 / span * WIDTH;
+    let length =
+        |start: u64, end: u64| ((u128::from(end - start) + 1) as f64 / span * WIDTH).max(1.0);
+
+    let mut body = String::new();
+    let mut y = GAP;
+    for group in &groups {
+        let assignments = &group.assignments;
+        let top = y;
+        for a in assignments {
+            writeln!(
+                body,
+                r#"<text x="4" y="{:.1}" font-size="{BAR}">{}-{}</text>"#,
+                y + BAR - 1.0,
+                a.start.id,
+                a.end.id
+            )?;
+            writeln!(
+                body,
+                r##"<rect x="{:.2}" y="{y:.1}" width="{:.2}" height="{BAR}" fill="#4682b4"/>"##,
+                x(a.start.id),
+                length(a.start.id, a.end.id)
+            )?;
+            y += BAR;
+        }
+        let (shared, contained) = shared(assignments);
+        let color = if contained { "#d62728" } else { "#ff7f0e" };
+        for (start, end) in shared {
+            writeln!(
+                body,
+                r#"<rect x="{:.2}" y="{top:.1}" width="{:.2}" height="{:.1}" fill="{color}" fill-opacity="0.6"/>"#,
+                x(start),
+                length(start, end),
+                y - top
+            )?;
+        }
+        y += GAP;
+    }
+    Ok(format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{y:.1}">
+{body}</svg>"#,
+        LABEL + WIDTH + GAP
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzle_style() {
+        let result = render_ascii("2-4,6-8\n2-8,3-7\n5-7,7-9", 60).unwrap();
+        assert_eq!(
+            result,
+            ".234.....  2-4
+.....678.  6-8
+
+.2345678.  2-8
+..34567..  3-7
+  ^^^^^    contained
+
+....567..  5-7
+......789  7-9
+      ^    overlap"
+        );
+    }
+
+    #[test]
+    fn test_scaled() {
+        let result = render_ascii("1-50,51-100\n20-60,40-80", 10).unwrap();
+        assert_eq!(
+            result,
+            "sections 1-100, 10 per column
+
+|#####.....|  1-50
+|.....#####|  51-100
+
+|.#####....|  20-60
+|...#####..|  40-80
+    ^^^      overlap"
+        );
+    }
+
+    #[test]
+    fn test_svg() {
+        let result = render_svg("2-4,6-8\n2-8,3-7").unwrap();
+        assert!(result.starts_with("<svg"));
+        assert!(result.ends_with("</svg>"));
+        assert_eq!(result.matches("<rect").count(), 5);
+        assert!(result.contains("#d62728"));
+    }
+
+    #[test]
+    fn test_section_zero() {
+        let result = render_ascii("0-2,3-4\n0-0,0-0", 60).unwrap();
+        assert_eq!(
+            result,
+            "sections 0-4, 1 per column
+
+|###..|  0-2
+|...##|  3-4
+
+|#....|  0-0
+|#....|  0-0
+ ^      contained"
+        );
+    }
+
+    #[test]
+    fn test_whole_range() {
+        let data = "0-18446744073709551615,1-2";
+        let result = render_ascii(data, 4).unwrap();
+        assert_eq!(
+            result,
+            "sections 0-18446744073709551615, 4611686018427387904 per column
+
+|####|  0-18446744073709551615
+|#...|  1-2
+ ^     contained"
+        );
+        let result = render_svg(data).unwrap();
+        assert_eq!(result.matches("<rect").count(), 3);
+    }
+}
@@ -0,0 +1,219 @@
+use std::ops::RangeInclusive;
+
+use crate::Assignment;
+
+// A set of sections kept as sorted ranges, merged so that no two of them
+// overlap or touch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SectionSet {
+    ranges: Vec<(u64, u64)>,
+}
+
+impl SectionSet {
+    pub fn new() -> Self {
+        SectionSet::default()
+    }
+
+    pub fn from_assignments<'a>(assignments: impl IntoIterator<Item = &'a Assignment>) -> Self {
+        assignments.into_iter().map(Assignment::range).collect()
+    }
+
+    fn normalised(mut ranges: Vec<(u64, u64)>) -> Self {
+        ranges.retain(|(start, end)| start <= end);
+        ranges.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start.saturating_sub(1) <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        SectionSet { ranges: merged }
+    }
+
+    pub fn insert(&mut self, range: RangeInclusive<u64>) {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.push(range.into_inner());
+        *self = SectionSet::normalised(ranges);
+    }
+
+    // The merged ranges, lowest first
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<u64>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..=end)
+    }
+
+    // Number of sections, which only fits a u128 when the set is every u64
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(start, end)| u128::from(end - start) + 1)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn contains(&self, section: u64) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end < section);
+        self.ranges
+            .get(i)
+            .is_some_and(|&(start, _)| start <= section)
+    }
+
+    pub fn is_superset(&self, other: &SectionSet) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    pub fn union(&self, other: &SectionSet) -> SectionSet {
+        SectionSet::normalised([&self.ranges[..], &other.ranges[..]].concat())
+    }
+
+    pub fn intersection(&self, other: &SectionSet) -> SectionSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+            match a_end < b_end {
+                true => i += 1,
+                false => j += 1,
+            }
+        }
+        SectionSet { ranges }
+    }
+
+    pub fn difference(&self, other: &SectionSet) -> SectionSet {
+        self.intersection(&other.complement(0..=u64::MAX))
+    }
+
+    // The sections within `bounds` not in the set
+    pub fn complement(&self, bounds: RangeInclusive<u64>) -> SectionSet {
+        let (low, high) = bounds.into_inner();
+        let mut ranges = Vec::new();
+        // The lowest section that may still be missing, None past u64::MAX
+        let mut next = Some(low);
+        for &(start, end) in &self.ranges {
+            let Some(from) = next.filter(|&from| from <= high) else {
+                break;
+            };
+            if start > from {
+                ranges.push((from, (start - 1).min(high)));
+            }
+            next = end.checked_add(1).map(|after| after.max(from));
+        }
+        if let Some(from) = next.filter(|&from| from <= high) {
+            ranges.push((from, high));
+        }
+        SectionSet { ranges }
+    }
+}
+
+impl FromIterator<RangeInclusive<u64>> for SectionSet {
+    fn from_iter<T: IntoIterator<Item = RangeInclusive<u64>>>(iter: T) -> Self {
+        SectionSet::normalised(iter.into_iter().map(RangeInclusive::into_inner).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sets within sections 0-63 as bitsets
+    fn bits(set: &SectionSet) -> u64 {
+        set.ranges()
+            .flatten()
+            .inspect(|&section| assert!(section < 64))
+            .fold(0, |bits, section| bits | 1 << section)
+    }
+
+    fn range_bits(range: &RangeInclusive<u64>) -> u64 {
+        range.clone().fold(0, |bits, section| bits | 1 << section)
+    }
+
+    fn assert_normalised(set: &SectionSet) {
+        assert!(set.ranges.iter().all(|(start, end)| start <= end));
+        assert!(set.ranges.windows(2).all(|w| w[0].1 + 1 < w[1].0));
+    }
+
+    #[test]
+    fn test_sections() {
+        let set = [5..=9, 1..=2, 3..=3, RangeInclusive::new(12, 11), 8..=10]
+            .into_iter()
+            .collect::<SectionSet>();
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![1..=3, 5..=10]);
+        assert_eq!(set.len(), 9);
+        assert!(set.contains(3) && !set.contains(4) && set.contains(10));
+        assert_eq!(
+            set.complement(0..=12).ranges().collect::<Vec<_>>(),
+            vec![0..=0, 4..=4, 11..=12]
+        );
+
+        let everything = SectionSet::from_iter([0..=u64::MAX]);
+        assert_eq!(everything.len(), 1 << 64);
+        assert!(everything.complement(0..=u64::MAX).is_empty());
+        let top = SectionSet::from_iter([u64::MAX - 1..=u64::MAX, 0..=0]);
+        assert_eq!(
+            top.complement(0..=u64::MAX).ranges().collect::<Vec<_>>(),
+            vec![1..=u64::MAX - 2]
+        );
+        assert_eq!(everything.difference(&top).len(), (1 << 64) - 3);
+        assert!(everything.is_superset(&top) && !top.is_superset(&everything));
+
+        let assignments = ["2-4", "6-8", "3-5"].map(|s| s.parse::<Assignment>().unwrap());
+        let set = SectionSet::from_assignments(&assignments);
+        assert_eq!(set.ranges().collect::<Vec<_>>(), vec![2..=8]);
+    }
+
+    #[test]
+    fn test_against_bitsets() {
+        let mut seed = 4_u64;
+        let mut next = |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        for _ in 0..2000 {
+            let mut random_set = || {
+                let ranges = (0..next(5))
+                    .map(|_| {
+                        let start = next(64);
+                        start..=(start + next(12)).min(63)
+                    })
+                    .collect::<Vec<_>>();
+                let oracle = ranges.iter().fold(0, |bits, r| bits | range_bits(r));
+                (ranges.into_iter().collect::<SectionSet>(), oracle)
+            };
+            let (a, a_bits) = random_set();
+            let (b, b_bits) = random_set();
+            let low = next(64);
+            let bounds = low..=(low + next(64)).min(63);
+
+            assert_normalised(&a);
+            assert_eq!(bits(&a), a_bits);
+            assert_eq!(a.len(), u128::from(a_bits.count_ones()));
+            assert_eq!(a.is_empty(), a_bits == 0);
+            for (set, oracle) in [
+                (a.union(&b), a_bits | b_bits),
+                (a.intersection(&b), a_bits & b_bits),
+                (a.difference(&b), a_bits & !b_bits),
+                (a.complement(bounds.clone()), !a_bits & range_bits(&bounds)),
+            ] {
+                assert_normalised(&set);
+                assert_eq!(bits(&set), oracle);
+            }
+            assert_eq!(a.is_superset(&b), b_bits & !a_bits == 0);
+            let section = next(64);
+            assert_eq!(a.contains(section), a_bits >> section & 1 == 1);
+
+            let mut inserted = a.clone();
+            inserted.insert(bounds.clone());
+            assert_eq!(bits(&inserted), a_bits | range_bits(&bounds));
+        }
+    }
+}
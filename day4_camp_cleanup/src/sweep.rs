@@ -0,0 +1,163 @@
+use std::{cmp::Reverse, collections::BinaryHeap, error::Error, ops::RangeInclusive};
+
+use crate::{groups, SectionSet};
+
+// An elf is the `position`-th assignment (from 0) of a 1-based `line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Elf {
+    pub line: usize,
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overlap {
+    pub elves: (Elf, Elf),
+    // Whether one assignment fully contains the other
+    pub contained: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    pub overlaps: Vec<Overlap>,
+    // Most elves assigned to one section, and the first such section
+    pub max_coverage: usize,
+    pub busiest_section: Option<u64>,
+    // Sections between the lowest and highest assigned ones nobody cleans
+    pub gaps: Vec<RangeInclusive<u64>>,
+}
+
+// Sweep the assignments by start section keeping the ones still open in a
+// heap by end section: every open assignment overlaps the one starting, so
+// the work is O(n log n) plus one step per reported overlap.
+pub fn sweep(data: &str) -> Result<Sweep, Box<dyn Error>> {
+    let mut assignments = groups(data)?
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, group)| {
+            group
+                .assignments
+                .into_iter()
+                .enumerate()
+                .map(move |(position, assignment)| {
+                    let elf = Elf {
+                        line: i + 1,
+                        position,
+                    };
+                    (assignment.start.id, Reverse(assignment.end.id), elf)
+                })
+        })
+        .collect::<Vec<_>>();
+    assignments.sort_unstable();
+
+    let mut result = Sweep {
+        overlaps: Vec::new(),
+        max_coverage: 0,
+        busiest_section: None,
+        gaps: Vec::new(),
+    };
+    let mut open = BinaryHeap::new();
+    for &(start, Reverse(end), elf) in &assignments {
+        while open
+            .peek()
+            .is_some_and(|&Reverse((open_end, _, _))| open_end < start)
+        {
+            open.pop();
+        }
+        // Open assignments start no later, so they contain this one when
+        // they also end no sooner
+        result.overlaps.extend(
+            open.iter()
+                .map(|&Reverse((open_end, open_elf, open_start))| Overlap {
+                    elves: (open_elf, elf),
+                    contained: end <= open_end || (start == open_start && open_end <= end),
+                }),
+        );
+        open.push(Reverse((end, elf, start)));
+        if open.len() > result.max_coverage {
+            result.max_coverage = open.len();
+            result.busiest_section = Some(start);
+        }
+    }
+
+    let covered = assignments
+        .iter()
+        .map(|&(start, Reverse(end), _)| start..=end)
+        .collect::<SectionSet>();
+    if let (Some(first), Some(last)) = (covered.ranges().next(), covered.ranges().last()) {
+        result.gaps = covered
+            .complement(*first.start()..=*last.end())
+            .ranges()
+            .collect();
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elf(line: usize, position: usize) -> Elf {
+        Elf { line, position }
+    }
+
+    #[test]
+    fn test_sweep() {
+        let result = sweep("2-4,6-8\n3-3,5-7\n12-15,1-2").unwrap();
+        let mut overlaps = result
+            .overlaps
+            .iter()
+            .map(|o| {
+                let (a, b) = o.elves;
+                (a.min(b), a.max(b), o.contained)
+            })
+            .collect::<Vec<_>>();
+        overlaps.sort();
+        assert_eq!(
+            overlaps,
+            vec![
+                (elf(1, 0), elf(2, 0), true),
+                (elf(1, 0), elf(3, 1), false),
+                (elf(1, 1), elf(2, 1), false),
+            ]
+        );
+        assert_eq!(result.max_coverage, 2);
+        assert_eq!(result.busiest_section, Some(2));
+        assert_eq!(result.gaps, vec![9..=11]);
+    }
+
+    #[test]
+    fn test_brute_force() {
+        const DATA: &str = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+        let result = sweep(DATA).unwrap();
+        let all = groups(DATA)
+            .unwrap()
+            .into_iter()
+            .flat_map(|group| group.assignments)
+            .collect::<Vec<_>>();
+        let mut expected = (0..all.len())
+            .flat_map(|a| (a + 1..all.len()).map(move |b| (a, b)))
+            .filter(|&(a, b)| all[a].one_overlaps_the_other(&all[b]))
+            .map(|(a, b)| (a, b, all[a].one_contains_the_other(&all[b])))
+            .collect::<Vec<_>>();
+        expected.sort();
+        let index = |elf: Elf| (elf.line - 1) * 2 + elf.position;
+        let mut found = result
+            .overlaps
+            .iter()
+            .map(|o| {
+                let (a, b) = (index(o.elves.0), index(o.elves.1));
+                (a.min(b), a.max(b), o.contained)
+            })
+            .collect::<Vec<_>>();
+        found.sort();
+        assert_eq!(found, expected);
+        // Sections 6 and 7 are assigned to eight elves each
+        assert_eq!(result.max_coverage, 8);
+        assert!(result.gaps.is_empty());
+    }
+}
@@ -0,0 +1,242 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, Write},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use crate::{CargoCrane, CraneModel, Crate, Procedure, Stack};
+
+const SOURCE: &str = "\x1b[33m";
+const DESTINATION: &str = "\x1b[32m";
+const IN_FLIGHT: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    // The procedure being carried out and its number, None at the start
+    pub procedure: Option<(usize, Procedure)>,
+    pub stacks: Vec<Stack>,
+    // Crates hanging from the crane above the source stack, bottom first
+    pub in_flight: Vec<Crate>,
+}
+
+// A frame with the crates lifted and another with them put down for every
+// lift of the crane: one crate at a time or the whole procedure at once.
+pub fn frames(crane: &CargoCrane, model: CraneModel) -> Vec<Frame> {
+    let mut stacks = crane.stacks.clone();
+    let mut frames = vec![Frame {
+        procedure: None,
+        stacks: stacks.clone(),
+        in_flight: Vec::new(),
+    }];
+    let done = crane.history.len();
+    for (i, &procedure) in crane.procedures.iter().enumerate().skip(done) {
+        let Procedure { quantity, from, to } = procedure;
+        // The crane stops when the stack runs out
+        let quantity = quantity.min(stacks[from - 1].crates.len());
+        let (lifts, lift) = match model {
+            CraneModel::OneAtATime => (quantity, 1),
+            CraneModel::MultipleAtOnce => (usize::from(quantity > 0), quantity),
+        };
+        for _ in 0..lifts {
+            let source = &mut stacks[from - 1].crates;
+            let in_flight = source.split_off(source.len() - lift);
+            frames.push(Frame {
+                procedure: Some((i + 1, procedure)),
+                stacks: stacks.clone(),
+                in_flight: in_flight.clone(),
+            });
+            stacks[to - 1].crates.extend(in_flight);
+            frames.push(Frame {
+                procedure: Some((i + 1, procedure)),
+                stacks: stacks.clone(),
+                in_flight: Vec::new(),
+            });
+        }
+    }
+    frames
+}
+
+impl Frame {
+    // The drawing with the crates in flight above it and `src`/`dst` under
+    // the stacks of the procedure, coloured with ANSI codes if `colour`.
+    pub fn render(&self, colour: bool) -> String {
+        let (from, to) = self.procedure.map_or((0, 0), |(_, p)| (p.from, p.to));
+        let paint = |text: String, code: Option<&str>| match (colour, code) {
+            (true, Some(code)) => format!("{code}{text}{RESET}"),
+            _ => text,
+        };
+        let highlight = |stack: usize| match stack {
+            _ if stack == from => Some(SOURCE),
+            _ if stack == to => Some(DESTINATION),
+            _ => None,
+        };
+        let line = |cells: Vec<String>| cells.join(" ").trim_end().to_string();
+
+        let mut lines = vec![match self.procedure {
+            Some((step, procedure)) => format!("Step {step}: {procedure}"),
+            None => "Start".to_string(),
+        }];
+        lines.push(String::new());
+        for crate_ in self.in_flight.iter().rev() {
+            let cells = (1..=self.stacks.len())
+                .map(|stack| match stack == from {
+                    true => paint(format!("[{}]", crate_.id), Some(IN_FLIGHT)),
+                    false => "   ".to_string(),
+                })
+                .collect();
+            lines.push(line(cells));
+        }
+        let height = self
+            .stacks
+            .iter()
+            .map(|s| s.crates.len())
+            .max()
+            .unwrap_or(0);
+        for level in (0..height).rev() {
+            let cells = self
+                .stacks
+                .iter()
+                .enumerate()
+                .map(|(i, stack)| match stack.crates.get(level) {
+                    Some(crate_) => paint(format!("[{}]", crate_.id), highlight(i + 1)),
+                    None => "   ".to_string(),
+                })
+                .collect();
+            lines.push(line(cells));
+        }
+        let labels = (1..=self.stacks.len())
+            .map(|stack| paint(format!("{stack:^3}"), highlight(stack)))
+            .collect();
+        lines.push(line(labels));
+        if self.procedure.is_some() {
+            let markers = (1..=self.stacks.len())
+                .map(|stack| match stack {
+                    _ if stack == from => paint("src".to_string(), Some(SOURCE)),
+                    _ if stack == to => paint("dst".to_string(), Some(DESTINATION)),
+                    _ => "   ".to_string(),
+                })
+                .collect();
+            lines.push(line(markers));
+        }
+        lines.join("\n")
+    }
+}
+
+// Redraw the terminal for every frame, waiting `delay` between them
+pub fn animate(crane: &CargoCrane, model: CraneModel, delay: Duration) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    for frame in frames(crane, model) {
+        write!(out, "{CLEAR}{}", frame.render(true))?;
+        writeln!(out)?;
+        out.flush()?;
+        thread::sleep(delay);
+    }
+    Ok(())
+}
+
+// Write the frames without colours to numbered files in `dir`
+pub fn write_frames(
+    crane: &CargoCrane,
+    model: CraneModel,
+    dir: &Path,
+) -> Result<usize, Box<dyn Error>> {
+    fs::create_dir_all(dir)?;
+    let frames = frames(crane, model);
+    for (i, frame) in frames.iter().enumerate() {
+        fs::write(
+            dir.join(format!("frame_{i:05}.txt")),
+            frame.render(false) + "\n",
+        )?;
+    }
+    Ok(frames.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    #[test]
+    fn test_frames() {
+        let crane = INPUT.parse::<CargoCrane>().unwrap();
+        let one_at_a_time = frames(&crane, CraneModel::OneAtATime);
+        assert_eq!(one_at_a_time.len(), 15);
+        assert_eq!(
+            one_at_a_time[1].render(false),
+            "Step 1: move 1 from 2 to 1
+
+    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+dst src"
+        );
+
+        let mut multiple_at_once = frames(&crane, CraneModel::MultipleAtOnce);
+        assert_eq!(multiple_at_once.len(), 9);
+        assert_eq!(
+            multiple_at_once[3].render(false),
+            "Step 2: move 3 from 1 to 3
+
+[D]
+[N]
+[Z]
+    [C]
+    [M] [P]
+ 1   2   3
+src     dst"
+        );
+        let mut crane = crane.clone();
+        crane.run(CraneModel::MultipleAtOnce);
+        assert_eq!(multiple_at_once.pop().unwrap().stacks, crane.stacks());
+    }
+
+    #[test]
+    fn test_colours() {
+        let crane = INPUT.parse::<CargoCrane>().unwrap();
+        let frame = &frames(&crane, CraneModel::OneAtATime)[1];
+        let rendered = frame.render(true);
+        assert!(rendered.contains(&format!("{IN_FLIGHT}[D]{RESET}")));
+        assert!(rendered.contains(&format!("{SOURCE}[C]{RESET}")));
+        assert!(rendered.contains(&format!("{DESTINATION}[N]{RESET}")));
+        assert!(rendered.contains(&format!("{DESTINATION}dst{RESET} {SOURCE}src{RESET}")));
+    }
+
+    #[test]
+    fn test_oversized_quantity() {
+        let input = "[B]\n[A]\n 1   2\n\nmove 18446744073709551615 from 1 to 2";
+        let crane = input.parse::<CargoCrane>().unwrap();
+        for (model, count) in [(CraneModel::OneAtATime, 5), (CraneModel::MultipleAtOnce, 3)] {
+            let frames = frames(&crane, model);
+            assert_eq!(frames.len(), count);
+            let mut crane = crane.clone();
+            crane.run(model);
+            assert_eq!(frames.last().unwrap().stacks, crane.stacks());
+        }
+    }
+
+    #[test]
+    fn test_write_frames() {
+        let dir = std::env::temp_dir().join(format!("day5_frames_{}", std::process::id()));
+        let crane = INPUT.parse::<CargoCrane>().unwrap();
+        let written = write_frames(&crane, CraneModel::MultipleAtOnce, &dir).unwrap();
+        let last = fs::read_to_string(dir.join("frame_00008.txt")).unwrap();
+        assert_eq!(written, fs::read_dir(&dir).unwrap().count());
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(last.ends_with(" 1   2   3\nsrc dst\n"));
+    }
+}
@@ -1,4 +1,12 @@
-use std::{error, fs, str::FromStr};
+use std::{
+    error,
+    fmt::{self, Display},
+    fs,
+    str::{FromStr, SplitWhitespace},
+};
+
+pub mod animate;
+pub mod plan;
 
 pub fn input() -> String {
     fs::read_to_string("input.txt")
@@ -10,10 +18,17 @@ pub fn input() -> String {
 // Advent of Code 2022
 // --- Day 5: Supply Stacks ---
 
-struct Crate {
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Crate {
     id: char,
 }
 
+impl Crate {
+    pub fn id(&self) -> char {
+        self.id
+    }
+}
+
 impl TryFrom<char> for Crate {
     type Error = Box<dyn error::Error>;
 
@@ -25,69 +40,187 @@ impl TryFrom<char> for Crate {
     }
 }
 
-struct Stack {
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Stack {
     crates: Vec<Crate>,
 }
 
-struct Procedure {
+impl Stack {
+    // Bottom crate first
+    pub fn crates(&self) -> &[Crate] {
+        &self.crates
+    }
+
+    pub fn top(&self) -> Option<&Crate> {
+        self.crates.last()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Procedure {
     quantity: usize,
     from: usize,
     to: usize,
 }
 
+impl Procedure {
+    pub fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    // Stacks are numbered from 1 like in the drawing
+    pub fn from(&self) -> usize {
+        self.from
+    }
+
+    pub fn to(&self) -> usize {
+        self.to
+    }
+}
+
+impl Display for Procedure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.quantity, self.from, self.to
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CraneModel {
+    // CrateMover 9000
+    OneAtATime,
+    // CrateMover 9001
+    MultipleAtOnce,
+}
+
+// A procedure as it was carried out: how many crates actually moved, fewer
+// than asked when the stack ran out, and by which crane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub procedure: Procedure,
+    pub model: CraneModel,
+    pub moved: usize,
+}
+
+fn apply(
+    stacks: &mut [Stack],
+    quantity: usize,
+    from: usize,
+    to: usize,
+    model: CraneModel,
+) -> usize {
+    let source = &mut stacks[from - 1].crates;
+    let moved = quantity.min(source.len());
+    // Putting crates back where they came from, either crane leaves them as
+    // they were
+    if from == to {
+        return moved;
+    }
+    let mut lifted = source.split_off(source.len() - moved);
+    if model == CraneModel::OneAtATime {
+        lifted.reverse();
+    }
+    stacks[to - 1].crates.extend(lifted);
+    moved
+}
+
+// The words of a procedure line, checked one at a time against the grammar
+// `move <quantity> from <stack> to <stack>`.
+struct Tokens<'a> {
+    words: SplitWhitespace<'a>,
+}
+
+impl Tokens<'_> {
+    fn found(word: Option<&str>) -> String {
+        word.map_or("end of line".to_string(), |word| format!("`{word}`"))
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), Box<dyn error::Error>> {
+        match self.words.next() {
+            Some(word) if word == keyword => Ok(()),
+            word => Err(format!("expected `{keyword}`, found {}", Tokens::found(word)).into()),
+        }
+    }
+
+    fn number(&mut self, after: &str) -> Result<usize, Box<dyn error::Error>> {
+        let word = self.words.next();
+        word.and_then(|word| word.parse().ok()).ok_or_else(|| {
+            format!(
+                "expected a number after `{after}`, found {}",
+                Tokens::found(word)
+            )
+            .into()
+        })
+    }
+
+    fn stack(&mut self, after: &str) -> Result<usize, Box<dyn error::Error>> {
+        match self.number(after)? {
+            0 => Err("stack 0 does not exist, stacks are numbered from 1".into()),
+            stack => Ok(stack),
+        }
+    }
+
+    fn end(&mut self) -> Result<(), Box<dyn error::Error>> {
+        match self.words.next() {
+            None => Ok(()),
+            word => Err(format!("unexpected {} after the procedure", Tokens::found(word)).into()),
+        }
+    }
+}
+
 impl FromStr for Procedure {
     type Err = Box<dyn error::Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s;
-        let quantity = s
-            .chars()
-            .take_while(|&c| c != 'f')
-            .filter(|c| c.is_numeric())
-            .flat_map(|c| c.to_digit(10))
-            .fold(0, |acc, x| acc * 10 + x);
-        let from = s
-            .chars()
-            .skip_while(|&c| c != 'f')
-            .take_while(|&c| c != 't')
-            .filter(|c| c.is_numeric())
-            .flat_map(|c| c.to_digit(10))
-            .fold(0, |acc, x| acc * 10 + x);
-        let to = s
-            .chars()
-            .skip_while(|&c| c != 't')
-            .filter(|c| c.is_numeric())
-            .flat_map(|c| c.to_digit(10))
-            .fold(0, |acc, x| acc * 10 + x);
-        Ok(Procedure {
-            quantity: quantity as usize,
-            from: from as usize,
-            to: to as usize,
-        })
+        let mut tokens = Tokens {
+            words: s.split_whitespace(),
+        };
+        tokens.keyword("move")?;
+        let quantity = tokens.number("move")?;
+        tokens.keyword("from")?;
+        let from = tokens.stack("from")?;
+        tokens.keyword("to")?;
+        let to = tokens.stack("to")?;
+        tokens.end()?;
+        Ok(Procedure { quantity, from, to })
     }
 }
 
-struct CargoCrane {
+#[derive(Debug, Clone)]
+pub struct CargoCrane {
+    initial: Vec<Stack>,
     stacks: Vec<Stack>,
     procedures: Vec<Procedure>,
+    history: Vec<Step>,
+    // Undone steps, the next one to redo last
+    undone: Vec<Step>,
 }
 
 impl FromStr for CargoCrane {
     type Err = Box<dyn error::Error>;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (stacks_drawing, procedures_drawing) =
-            s.split_once("\n\n").ok_or("Error parsing Drawing")?;
-
-        let procedures = procedures_drawing
-            .lines()
-            .flat_map(|line| line.parse())
-            .collect::<Vec<Procedure>>();
+        // A drawing on its own has no procedures left to carry out
+        let (stacks_drawing, procedures_drawing) = s.split_once("\n\n").unwrap_or((s, ""));
 
         let mut stacks_lines = stacks_drawing.lines().rev();
-        let mut stacks = stacks_lines
-            .next()
-            .ok_or("Error parsing stacks")?
+        let labels = stacks_lines.next().ok_or("Error parsing stacks")?;
+        // The drawing ends with the stacks numbered 1, 2, ... in order
+        let numbered = labels
+            .split_whitespace()
+            .enumerate()
+            .all(|(i, label)| label.parse() == Ok(i + 1));
+        if labels.trim().is_empty() || !numbered {
+            return Err(format!(
+                "line {}: expected the stack numbers, found `{labels}`",
+                stacks_drawing.lines().count()
+            )
+            .into());
+        }
+        let mut stacks = labels
             .split_whitespace()
             .map(|_| Stack { crates: Vec::new() })
             .collect::<Vec<Stack>>();
@@ -103,12 +236,52 @@ impl FromStr for CargoCrane {
                 })
         });
 
-        Ok(CargoCrane { stacks, procedures })
+        // Procedures start after the drawing and the blank line
+        let first_line = stacks_drawing.lines().count() + 2;
+        let procedures = procedures_drawing
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let error = |e: Box<dyn error::Error>| format!("line {}: {e}", first_line + i);
+                let procedure = line.parse::<Procedure>().map_err(error)?;
+                if let Some(stack) = [procedure.from, procedure.to]
+                    .into_iter()
+                    .find(|&stack| stack > stacks.len())
+                {
+                    return Err(error(
+                        format!("stack {stack} does not exist, there are {}", stacks.len()).into(),
+                    ));
+                }
+                Ok(procedure)
+            })
+            .collect::<Result<Vec<Procedure>, _>>()?;
+
+        Ok(CargoCrane {
+            initial: stacks.clone(),
+            stacks,
+            procedures,
+            history: Vec::new(),
+            undone: Vec::new(),
+        })
     }
 }
 
 impl CargoCrane {
-    fn result(&self) -> String {
+    pub fn stacks(&self) -> &[Stack] {
+        &self.stacks
+    }
+
+    pub fn procedures(&self) -> &[Procedure] {
+        &self.procedures
+    }
+
+    // The steps carried out so far, one per procedure from the first
+    pub fn history(&self) -> &[Step] {
+        &self.history
+    }
+
+    pub fn result(&self) -> String {
         self.stacks
             .iter()
             .flat_map(|stack| stack.crates.last())
@@ -116,44 +289,101 @@ impl CargoCrane {
             .collect()
     }
 
-    fn rearrange_one_at_a_time(&mut self) {
-        self.procedures
-            .iter()
-            .for_each(|&Procedure { quantity, from, to }| {
-                (0..quantity).for_each(|_| {
-                    if let Some(crate_) = self.stacks[from - 1].crates.pop() {
-                        self.stacks[to - 1].crates.push(crate_);
-                    }
-                })
-            });
+    // Carry out the next procedure, forgetting any undone steps
+    pub fn step(&mut self, model: CraneModel) -> Option<Step> {
+        let procedure = *self.procedures.get(self.history.len())?;
+        let Procedure { quantity, from, to } = procedure;
+        let moved = apply(&mut self.stacks, quantity, from, to, model);
+        let step = Step {
+            procedure,
+            model,
+            moved,
+        };
+        self.history.push(step);
+        self.undone.clear();
+        Some(step)
+    }
+
+    // Moving the crates back with the same crane restores their order
+    pub fn undo(&mut self) -> Option<Step> {
+        let step = self.history.pop()?;
+        let Procedure { from, to, .. } = step.procedure;
+        apply(&mut self.stacks, step.moved, to, from, step.model);
+        self.undone.push(step);
+        Some(step)
+    }
+
+    pub fn redo(&mut self) -> Option<Step> {
+        let step = self.undone.pop()?;
+        let Procedure { from, to, .. } = step.procedure;
+        apply(&mut self.stacks, step.moved, from, to, step.model);
+        self.history.push(step);
+        Some(step)
+    }
+
+    pub fn run(&mut self, model: CraneModel) {
+        while self.step(model).is_some() {}
     }
 
-    fn rearrange_multiple_at_once(&mut self) {
-        self.procedures
+    // The stacks after the first `n` steps of the history
+    pub fn snapshot(&self, n: usize) -> Option<Vec<Stack>> {
+        let mut stacks = self.initial.clone();
+        for step in self.history.get(..n)? {
+            let Procedure { from, to, .. } = step.procedure;
+            apply(&mut stacks, step.moved, from, to, step.model);
+        }
+        Some(stacks)
+    }
+}
+
+// The stacks drawn like the puzzle input, followed by the procedures still
+// to carry out, so the output parses back to the same crane.
+impl Display for CargoCrane {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let height = self
+            .stacks
             .iter()
-            .for_each(|&Procedure { quantity, from, to }| {
-                let mut vec_temp = Vec::<Crate>::with_capacity(quantity);
-                (0..quantity).for_each(|_| {
-                    if let Some(crate_) = self.stacks[from - 1].crates.pop() {
-                        vec_temp.push(crate_);
-                    }
-                });
-                self.stacks[to - 1]
-                    .crates
-                    .extend(vec_temp.into_iter().rev());
-            });
+            .map(|s| s.crates.len())
+            .max()
+            .unwrap_or(0);
+        for level in (0..height).rev() {
+            let row = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.crates.get(level) {
+                    Some(crate_) => format!("[{}]", crate_.id),
+                    None => "   ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        let labels = (1..=self.stacks.len())
+            .map(|i| format!("{i:^3}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        write!(f, "{}", labels.trim_end())?;
+
+        let pending = &self.procedures[self.history.len()..];
+        if !pending.is_empty() {
+            writeln!(f)?;
+        }
+        for procedure in pending {
+            write!(f, "\n{procedure}")?;
+        }
+        Ok(())
     }
 }
 
 pub fn part1(input: &str) -> Result<String, Box<dyn error::Error>> {
     let mut cargo_crane = input.parse::<CargoCrane>()?;
-    cargo_crane.rearrange_one_at_a_time();
+    cargo_crane.run(CraneModel::OneAtATime);
     Ok(cargo_crane.result())
 }
 
 pub fn part2(input: &str) -> Result<String, Box<dyn error::Error>> {
     let mut cargo_crane = input.parse::<CargoCrane>()?;
-    cargo_crane.rearrange_multiple_at_once();
+    cargo_crane.run(CraneModel::MultipleAtOnce);
     Ok(cargo_crane.result())
 }
 
@@ -182,4 +412,180 @@ move 1 from 1 to 2";
         let result = part2(INPUT).unwrap();
         assert_eq!(result, "MCD");
     }
+
+    #[test]
+    fn test_procedure_errors() {
+        let drawing = INPUT.split_once("\n\n").unwrap().0;
+        let error = |procedure: &str| {
+            format!("{drawing}\n\nmove 1 from 2 to 1\n{procedure}")
+                .parse::<CargoCrane>()
+                .err()
+                .map(|e| e.to_string())
+        };
+        assert_eq!(error("move 3 from 1 to 3"), None);
+        assert_eq!(
+            error("mvoe 1 frm 2 to 1").unwrap(),
+            "line 7: expected `move`, found `mvoe`"
+        );
+        assert_eq!(
+            error("move 1 frm 2 to 1").unwrap(),
+            "line 7: expected `from`, found `frm`"
+        );
+        assert_eq!(
+            error("move from 2 to 1").unwrap(),
+            "line 7: expected a number after `move`, found `from`"
+        );
+        assert_eq!(
+            error("move 1 from 2 to").unwrap(),
+            "line 7: expected a number after `to`, found end of line"
+        );
+        assert_eq!(
+            error("move 1 from 0 to 1").unwrap(),
+            "line 7: stack 0 does not exist, stacks are numbered from 1"
+        );
+        assert_eq!(
+            error("move 1 from 2 to 4").unwrap(),
+            "line 7: stack 4 does not exist, there are 3"
+        );
+        assert_eq!(
+            error("move 1 from 2 to 1 please").unwrap(),
+            "line 7: unexpected `please` after the procedure"
+        );
+    }
+
+    fn tops(stacks: &[Stack]) -> String {
+        stacks.iter().flat_map(Stack::top).map(Crate::id).collect()
+    }
+
+    #[test]
+    fn test_steps() {
+        let mut crane = INPUT.parse::<CargoCrane>().unwrap();
+        let initial = crane.stacks().to_vec();
+        crane.step(CraneModel::OneAtATime);
+        let step = crane.step(CraneModel::MultipleAtOnce).unwrap();
+        assert_eq!(step.moved, 3);
+        assert_eq!(crane.result(), "CD");
+        assert_eq!(
+            crane.stacks()[2]
+                .crates()
+                .iter()
+                .map(Crate::id)
+                .collect::<String>(),
+            "PZND"
+        );
+
+        let after_two = crane.stacks().to_vec();
+        assert_eq!(crane.undo(), Some(step));
+        assert_eq!(tops(crane.stacks()), "DCP");
+        assert_eq!(crane.redo(), Some(step));
+        assert_eq!(crane.stacks(), after_two);
+        assert_eq!(crane.redo(), None);
+
+        crane.run(CraneModel::MultipleAtOnce);
+        assert_eq!(crane.history().len(), 4);
+        assert_eq!(crane.step(CraneModel::OneAtATime), None);
+        assert_eq!(crane.snapshot(2).unwrap(), after_two);
+        assert_eq!(crane.snapshot(0).unwrap(), initial);
+        assert_eq!(crane.snapshot(5), None);
+        assert_eq!(tops(&crane.snapshot(4).unwrap()), crane.result());
+
+        while crane.undo().is_some() {}
+        assert_eq!(crane.stacks(), initial);
+        crane.redo();
+        crane.step(CraneModel::OneAtATime);
+        assert_eq!(crane.redo(), None);
+    }
+
+    #[test]
+    fn test_short_stack() {
+        let mut crane = "[A]\n 1   2\n\nmove 3 from 1 to 2"
+            .parse::<CargoCrane>()
+            .unwrap();
+        assert_eq!(crane.step(CraneModel::OneAtATime).unwrap().moved, 1);
+        assert_eq!(crane.result(), "A");
+        crane.undo();
+        assert_eq!(tops(crane.stacks()), "A");
+        assert!(crane.stacks()[1].crates().is_empty());
+    }
+
+    #[test]
+    fn test_render() {
+        let mut crane = INPUT.parse::<CargoCrane>().unwrap();
+        assert_eq!(crane.to_string(), INPUT);
+        crane.step(CraneModel::OneAtATime);
+        crane.step(CraneModel::OneAtATime);
+        let drawing = "        [Z]
+        [N]
+    [C] [D]
+    [M] [P]
+ 1   2   3
+
+move 2 from 2 to 1
+move 1 from 1 to 2";
+        assert_eq!(crane.to_string(), drawing);
+        crane.run(CraneModel::OneAtATime);
+        assert_eq!(
+            crane.to_string(),
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let drawings = [
+            INPUT.to_string(),
+            " 1   2".to_string(),
+            "[A]\n 1   2\n\nmove 1 from 1 to 2".to_string(),
+            // Ten stacks, so the last label is two digits wide
+            format!("[X]{}[Y]\n", " ".repeat(33))
+                + &(0..10)
+                    .map(|i| format!("[{}]", char::from(b'A' + i)))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                + "\n 1   2   3   4   5   6   7   8   9  10\n\nmove 1 from 10 to 1",
+        ];
+        for drawing in drawings {
+            let crane = drawing.parse::<CargoCrane>().unwrap();
+            let rendered = crane.to_string();
+            assert_eq!(rendered, drawing);
+            let parsed = rendered.parse::<CargoCrane>().unwrap();
+            assert_eq!(parsed.stacks(), crane.stacks());
+            assert_eq!(parsed.procedures(), crane.procedures());
+            assert_eq!(parsed.to_string(), rendered);
+        }
+    }
+
+    #[test]
+    fn test_labels() {
+        let error = part1("[A]\n 1   2\nmove 1 from 1 to 2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "line 3: expected the stack numbers, found `move 1 from 1 to 2`"
+        );
+        let error = part1("[A]\n 1   3\n\nmove 1 from 1 to 2")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(error, "line 2: expected the stack numbers, found ` 1   3`");
+        assert_eq!(part1("[A]\n 1   2").unwrap(), "A");
+    }
+
+    #[test]
+    fn test_same_stack() {
+        let input = "[C]\n[B]\n[A]\n 1   2\n\nmove 3 from 1 to 1";
+        assert_eq!(part1(input).unwrap(), "C");
+        assert_eq!(part2(input).unwrap(), "C");
+        for model in [CraneModel::OneAtATime, CraneModel::MultipleAtOnce] {
+            let mut crane = input.parse::<CargoCrane>().unwrap();
+            let initial = crane.stacks().to_vec();
+            assert_eq!(crane.step(model).unwrap().moved, 3);
+            assert_eq!(crane.stacks(), initial);
+            assert_eq!(crane.snapshot(1).unwrap(), initial);
+            crane.undo();
+            assert_eq!(crane.stacks(), initial);
+            let frames = animate::frames(&crane, model);
+            assert_eq!(frames.last().unwrap().stacks, initial);
+        }
+    }
 }
@@ -1,8 +1,75 @@
-use std::error;
+use std::{env, error, fs, path::Path, time::Duration};
 
 use day5_supply_stacks::*;
 
+fn model(part: Option<String>) -> Result<CraneModel, Box<dyn error::Error>> {
+    match part.as_deref() {
+        Some("1") => Ok(CraneModel::OneAtATime),
+        Some("2") => Ok(CraneModel::MultipleAtOnce),
+        _ => Err("Expected part 1 or 2".into()),
+    }
+}
+
 fn main() -> Result<(), Box<dyn error::Error>> {
+    // --animate 1|2 [--delay ms] [--frames dir]
+    if let Some(i) = env::args().position(|arg| arg == "--animate") {
+        let model = model(env::args().nth(i + 1))?;
+        let crane = input().parse::<CargoCrane>()?;
+        let option = |name: &str| {
+            env::args()
+                .position(|arg| arg == name)
+                .map(|i| {
+                    env::args()
+                        .nth(i + 1)
+                        .ok_or(format!("Missing value for {name}"))
+                })
+                .transpose()
+        };
+        if let Some(dir) = option("--frames")? {
+            let written = animate::write_frames(&crane, model, Path::new(&dir))?;
+            println!("{written} frames written to {dir}");
+        } else {
+            let delay = option("--delay")?.map_or(Ok(100), |ms| ms.parse())?;
+            animate::animate(&crane, model, Duration::from_millis(delay))?;
+        }
+        return Ok(());
+    }
+    // --plan <target drawing> 1|2
+    if let Some(i) = env::args().position(|arg| arg == "--plan") {
+        let path = env::args().nth(i + 1).ok_or("Missing target drawing")?;
+        let target = fs::read_to_string(path)?.trim_end().parse::<CargoCrane>()?;
+        let initial = input().parse::<CargoCrane>()?;
+        println!(
+            "{}",
+            plan::plan(&initial, &target, model(env::args().nth(i + 2))?)?
+        );
+        return Ok(());
+    }
+    if let Some(i) = env::args().position(|arg| arg == "--render") {
+        let mut crane = input().parse::<CargoCrane>()?;
+        crane.run(model(env::args().nth(i + 1))?);
+        println!("{crane}");
+        return Ok(());
+    }
+    if let Some(i) = env::args().position(|arg| arg == "--snapshot") {
+        let n = env::args()
+            .nth(i + 1)
+            .ok_or("Missing step number")?
+            .parse()?;
+        for (part, model) in [(1, CraneModel::OneAtATime), (2, CraneModel::MultipleAtOnce)] {
+            let mut crane = input().parse::<CargoCrane>()?;
+            crane.run(model);
+            let stacks = crane
+                .snapshot(n)
+                .ok_or(format!("Only {} steps to take", crane.procedures().len()))?;
+            let tops = stacks
+                .iter()
+                .map(|stack| stack.top().map_or(' ', Crate::id))
+                .collect::<String>();
+            println!("Part {part} after step {n}: {tops}");
+        }
+        return Ok(());
+    }
     println!("Part 1: {}", part1(&input())?);
     println!("Part 2: {}", part2(&input())?);
     Ok(())
@@ -0,0 +1,182 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    error::Error,
+};
+
+use crate::{apply, CargoCrane, CraneModel, Procedure, Stack};
+
+// Give up rather than exhaust the memory on hopeless targets
+const STATE_LIMIT: usize = 2_000_000;
+
+// Every procedure takes crates from one stack and puts them on one other, so
+// it at best fixes one stack that has extra crates over the target and one
+// that lacks crates of the target.
+fn heuristic(stacks: &[Stack], target: &[Stack]) -> usize {
+    let (mut removals, mut additions) = (0, 0);
+    for (stack, target) in stacks.iter().zip(target) {
+        let common = stack
+            .crates
+            .iter()
+            .zip(&target.crates)
+            .take_while(|(a, b)| a == b)
+            .count();
+        removals += usize::from(stack.crates.len() > common);
+        additions += usize::from(target.crates.len() > common);
+    }
+    removals.max(additions)
+}
+
+fn sorted_ids(stacks: &[Stack]) -> Vec<char> {
+    let mut ids = stacks
+        .iter()
+        .flat_map(|stack| stack.crates.iter().map(|crate_| crate_.id))
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids
+}
+
+// A* search for the fewest procedures turning the current stacks of
+// `initial` into those of `target`. The result is a crane with the initial
+// stacks and the procedures found.
+pub fn plan(
+    initial: &CargoCrane,
+    target: &CargoCrane,
+    model: CraneModel,
+) -> Result<CargoCrane, Box<dyn Error>> {
+    let (start, goal) = (initial.stacks.clone(), target.stacks.clone());
+    if start.len() != goal.len() {
+        return Err(format!(
+            "The target has {} stacks instead of {}",
+            goal.len(),
+            start.len()
+        )
+        .into());
+    }
+    if sorted_ids(&start) != sorted_ids(&goal) {
+        return Err("The target does not have the same crates".into());
+    }
+
+    // Every state seen, with the cost to reach it and how
+    let mut states = vec![start.clone()];
+    let mut seen = HashMap::from([(start.clone(), 0)]);
+    let mut reached: Vec<(usize, Option<(usize, Procedure)>)> = vec![(0, None)];
+    let mut open = BinaryHeap::from([Reverse((heuristic(&start, &goal), 0, 0))]);
+    while let Some(Reverse((_, cost, state))) = open.pop() {
+        if cost > reached[state].0 {
+            continue;
+        }
+        if states[state] == goal {
+            let mut procedures = Vec::new();
+            let mut state = state;
+            while let Some((previous, procedure)) = reached[state].1 {
+                procedures.push(procedure);
+                state = previous;
+            }
+            procedures.reverse();
+            return Ok(CargoCrane {
+                initial: start.clone(),
+                stacks: start,
+                procedures,
+                history: Vec::new(),
+                undone: Vec::new(),
+            });
+        }
+        for from in 1..=goal.len() {
+            for to in (1..=goal.len()).filter(|&to| to != from) {
+                for quantity in 1..=states[state][from - 1].crates.len() {
+                    let mut stacks = states[state].clone();
+                    apply(&mut stacks, quantity, from, to, model);
+                    let procedure = Procedure { quantity, from, to };
+                    let next = match seen.get(&stacks) {
+                        Some(&next) if reached[next].0 <= cost + 1 => continue,
+                        Some(&next) => next,
+                        None => {
+                            if states.len() == STATE_LIMIT {
+                                return Err(
+                                    format!("No plan found within {STATE_LIMIT} states").into()
+                                );
+                            }
+                            states.push(stacks.clone());
+                            seen.insert(stacks, states.len() - 1);
+                            reached.push((usize::MAX, None));
+                            states.len() - 1
+                        }
+                    };
+                    reached[next] = (cost + 1, Some((state, procedure)));
+                    let estimate = cost + 1 + heuristic(&states[next], &goal);
+                    open.push(Reverse((estimate, cost + 1, next)));
+                }
+            }
+        }
+    }
+    Err("The target cannot be reached".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "    [D]
+[N] [C]
+[Z] [M] [P]
+ 1   2   3
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+    fn crane(drawing: &str) -> CargoCrane {
+        drawing.parse().unwrap()
+    }
+
+    #[test]
+    fn test_plan() {
+        let initial = crane("[C]\n[B]\n[A]\n 1   2   3");
+        let target = crane("    [C]\n    [B]\n    [A]\n 1   2   3");
+        let result = plan(&initial, &target, CraneModel::MultipleAtOnce).unwrap();
+        assert_eq!(
+            result.to_string(),
+            "[C]\n[B]\n[A]\n 1   2   3\n\nmove 3 from 1 to 2"
+        );
+        // Reversing twice through the third stack keeps the order
+        let mut result = plan(&initial, &target, CraneModel::OneAtATime).unwrap();
+        assert_eq!(result.procedures().len(), 2);
+        result.run(CraneModel::OneAtATime);
+        assert_eq!(result.stacks(), target.stacks());
+
+        let result = plan(&initial, &initial, CraneModel::OneAtATime).unwrap();
+        assert!(result.procedures().is_empty());
+    }
+
+    #[test]
+    fn test_puzzle_targets() {
+        for model in [CraneModel::OneAtATime, CraneModel::MultipleAtOnce] {
+            let initial = crane(INPUT);
+            let mut target = initial.clone();
+            target.run(model);
+            let mut result = plan(&initial, &target, model).unwrap();
+            assert!(result.procedures().len() <= initial.procedures().len());
+            let rendered = result.to_string();
+            result.run(model);
+            assert_eq!(result.stacks(), target.stacks());
+            assert_eq!(crane(&rendered).procedures(), result.procedures());
+        }
+    }
+
+    #[test]
+    fn test_mismatched_targets() {
+        let initial = crane(INPUT);
+        let error = plan(&initial, &crane("[Z]\n 1   2   3"), CraneModel::OneAtATime);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "The target does not have the same crates"
+        );
+        let error = plan(&initial, &crane(" 1"), CraneModel::OneAtATime);
+        assert_eq!(
+            error.unwrap_err().to_string(),
+            "The target has 1 stacks instead of 3"
+        );
+    }
+}